@@ -1,5 +1,3 @@
-use surrealdb::sql::statements::BeginStatement;
-use surrealdb::sql::statements::CommitStatement;
 use surrealdb_rs::param::Root;
 use surrealdb_rs::protocol::Ws;
 use surrealdb_rs::Surreal;
@@ -20,16 +18,17 @@ async fn main() -> surrealdb_rs::Result<()> {
     client.use_ns("namespace").use_db("database").await?;
 
     let results = client
-        // Start transaction
-        .query(BeginStatement)
-        // Setup accounts
-        .query("CREATE account:one SET balance = 135605.16")
-        .query("CREATE account:two SET balance = 91031.31")
-        // Move money
-        .query("UPDATE account:one SET balance += 300.00")
-        .query("UPDATE account:two SET balance -= 300.00")
-        // Finalise
-        .query(CommitStatement)
+        // Retried automatically if it conflicts with another transaction
+        .transaction_with(|mut transaction| async move {
+            transaction
+                // Setup accounts
+                .query("CREATE account:one SET balance = 135605.16")
+                .query("CREATE account:two SET balance = 91031.31")
+                // Move money
+                .query("UPDATE account:one SET balance += 300.00")
+                .query("UPDATE account:two SET balance -= 300.00");
+            Ok::<_, surrealdb_rs::Error>(transaction)
+        })
         .await?;
 
     for result in results {
@@ -33,6 +33,22 @@ pub enum ErrorKind {
     Deserialization,
     /// Serialization error
     Serialization,
+    /// Tried to start a live query using a protocol that doesn't support them
+    LiveQueriesNotSupported,
+    /// Live query notifications may have been missed while the connection was down
+    NotificationsMissed,
+    /// A transaction failed because it conflicted with another one and can be retried
+    TransactionConflict,
+    /// Tried to call a method that the connection doesn't support
+    MethodNotSupported,
+    /// The client does not support the version of the server
+    VersionMismatch,
+    /// An operation did not complete in time
+    Timeout,
+    /// The connection dropped before the server responded
+    ConnectionLost,
+    /// Tried to use a connection that was closed
+    ConnectionClosed,
 }
 
 impl ErrorKind {
@@ -50,7 +66,9 @@ impl ErrorKind {
             ErrorKind::DuplicateRequestId => {
                 format!("request ID {context} is already being used by another query")
             }
-            ErrorKind::Query => format!("failed to perform query; {context}"),
+            ErrorKind::Query | ErrorKind::TransactionConflict => {
+                format!("failed to perform query; {context}")
+            }
             ErrorKind::RangeUnsupported => format!("range not supported for {context}"),
             ErrorKind::Socket => format!("socket error; {context}"),
             ErrorKind::SyntaxUnsupported => format!("{context} syntax is not supported"),
@@ -61,8 +79,24 @@ impl ErrorKind {
             message,
         }
     }
+
+    /// Categorises an error message returned by a query
+    ///
+    /// The server has no error codes for query failures. When a transaction conflicts with
+    /// another one it fails with "Failed to commit transaction due to a read or write conflict.
+    /// This transaction can be retried", and the end of that message is the only thing which
+    /// tells clients the failure is safe to retry.
+    pub(crate) fn from_query_error(message: &str) -> Self {
+        match message.contains(RETRYABLE) {
+            true => ErrorKind::TransactionConflict,
+            false => ErrorKind::Query,
+        }
+    }
 }
 
+/// The part of the message the server fails conflicting transactions with
+const RETRYABLE: &str = "This transaction can be retried";
+
 /// Error type returned by the client
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Error {
@@ -201,3 +235,28 @@ impl From<pharos::PharErr> for Error {
         ErrorKind::Socket.with_context(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_conflicts_are_retryable() {
+        let message = "Failed to commit transaction due to a read or write conflict. \
+            This transaction can be retried";
+        assert_eq!(
+            ErrorKind::from_query_error(message),
+            ErrorKind::TransactionConflict
+        );
+    }
+
+    #[test]
+    fn other_query_errors_are_not_retryable() {
+        let message = "There was a problem with the database: The table does not exist";
+        assert_eq!(ErrorKind::from_query_error(message), ErrorKind::Query);
+        assert_eq!(
+            ErrorKind::from_query_error("the transaction can be retried later"),
+            ErrorKind::Query
+        );
+    }
+}
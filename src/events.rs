@@ -0,0 +1,131 @@
+use flume::r#async::RecvStream;
+use flume::Sender;
+use futures::StreamExt;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+/// A change in the state of the connection to the server
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConnectionEvent {
+    /// The client is connected to the server
+    Connected,
+    /// The connection to the server dropped
+    Disconnected {
+        /// Why the connection dropped
+        reason: String,
+    },
+    /// The client is trying to connect to the server again
+    Reconnecting {
+        /// How many times the client has tried so far, starting from `1`
+        attempt: u32,
+    },
+    /// The client connected to the server again
+    Reconnected,
+    /// The connection was closed for good
+    Closed,
+}
+
+/// Tracks the state of a connection and who to tell when it changes
+#[derive(Debug)]
+pub(crate) struct Events {
+    // the state and the subscribers share a lock, so that subscribers never miss a change
+    // nor hear about one they already learned of when subscribing
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    connected: bool,
+    closed: bool,
+    subscribers: Vec<Sender<ConnectionEvent>>,
+}
+
+impl Events {
+    /// Tracks a connection that was just established
+    pub(crate) fn connected() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                connected: true,
+                closed: false,
+                subscribers: Vec::new(),
+            }),
+        })
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.state.lock().unwrap().connected
+    }
+
+    pub(crate) fn subscribe(&self) -> ConnectionEvents {
+        let (sender, receiver) = flume::unbounded();
+        let mut state = self.state.lock().unwrap();
+        if state.connected {
+            let _res = sender.send(ConnectionEvent::Connected);
+        }
+        if state.closed {
+            let _res = sender.send(ConnectionEvent::Closed);
+        } else {
+            state.subscribers.push(sender);
+        }
+        ConnectionEvents {
+            receiver: receiver.into_stream(),
+        }
+    }
+
+    /// Records a change in the state of the connection and tells the subscribers about it
+    ///
+    /// Nothing is reported once the connection is closed.
+    pub(crate) fn emit(&self, event: ConnectionEvent) {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return;
+        }
+        match event {
+            ConnectionEvent::Connected | ConnectionEvent::Reconnected => {
+                state.connected = true;
+            }
+            ConnectionEvent::Disconnected { .. } | ConnectionEvent::Reconnecting { .. } => {
+                state.connected = false;
+            }
+            ConnectionEvent::Closed => {
+                state.connected = false;
+                state.closed = true;
+            }
+        }
+        state
+            .subscribers
+            .retain(|sender| sender.send(event.clone()).is_ok());
+        if let ConnectionEvent::Closed = event {
+            // ends the streams of the subscribers
+            state.subscribers.clear();
+        }
+    }
+}
+
+/// A stream of changes in the state of the connection
+///
+/// Starts with [`ConnectionEvent::Connected`] if the client is connected when the stream
+/// is created and ends after [`ConnectionEvent::Closed`].
+#[must_use = "streams do nothing unless you poll them"]
+pub struct ConnectionEvents {
+    receiver: RecvStream<'static, ConnectionEvent>,
+}
+
+impl futures::Stream for ConnectionEvents {
+    type Item = ConnectionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl fmt::Debug for ConnectionEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionEvents").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,340 @@
+//! Record and replay connections for deterministic tests
+//!
+//! Connect using the [`Record`] scheme to send requests to a real server. Each request and
+//! the response it got are appended to a fixture file. Load that file later with [`replay`]
+//! to serve the same responses offline through a [`MockServer`].
+//!
+//! Values are recorded in the form `SurrealDB` exchanges them with the server in, so record
+//! IDs, datetimes, durations, UUIDs and decimals are replayed as they were received.
+//!
+//! Each request has to be replayed with the same method and parameters it was recorded with.
+//! A request that doesn't match any recorded one fails with
+//! [`ErrorKind::InvalidRequest`](crate::ErrorKind::InvalidRequest). Live query notifications
+//! are passed through while recording, but they are not recorded.
+//!
+//! # Examples
+//!
+//! Record a fixture against a running server
+//!
+//! ```no_run
+//! use surrealdb_rs::fixture::Record;
+//! use surrealdb_rs::protocol::Ws;
+//! use surrealdb_rs::Surreal;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb_rs::Result<()> {
+//! let client = Surreal::connect::<Record<Ws>>(("localhost:8000", "fixtures/users.msgpack")).await?;
+//! client.use_ns("namespace").use_db("database").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Then replay it without a server
+//!
+//! ```no_run
+//! use surrealdb_rs::fixture;
+//! use surrealdb_rs::mock::Mock;
+//! use surrealdb_rs::Surreal;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb_rs::Result<()> {
+//! let server = fixture::replay("fixtures/users.msgpack")?;
+//! let client = Surreal::connect::<Mock>(&server).await?;
+//! client.use_ns("namespace").use_db("database").await?;
+//! server.verify();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::mock::MockServer;
+use crate::param::from_value;
+use crate::param::DbResponse;
+use crate::param::Harness;
+use crate::param::InternalSerialization;
+use crate::param::Param;
+use crate::param::ServerAddrs;
+use crate::session::Session;
+use crate::Connection;
+use crate::Error;
+use crate::ErrorKind;
+use crate::ExtractRouter;
+use crate::Method;
+use crate::Requests;
+use crate::Response as QueryResponse;
+use crate::Result;
+use crate::Route;
+use crate::Router;
+use crate::Stats;
+use crate::Status;
+use crate::Surreal;
+use flume::Receiver;
+use futures::StreamExt;
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::pin::Pin;
+#[cfg(feature = "ws")]
+use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
+use std::time::Duration;
+use surrealdb::sql::Value;
+
+type RecordRoute = Route<(Method, Param), Result<DbResponse>>;
+
+/// The scheme used to record a connection made using the scheme `S`
+#[derive(Debug)]
+pub struct Record<S>(PhantomData<S>);
+
+/// A client that records the requests sent through the client `C` into a fixture file
+#[derive(Debug, Clone)]
+pub struct Client<C> {
+    method: Method,
+    conn: PhantomData<C>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    method: Method,
+    params: Vec<Value>,
+    response: std::result::Result<Recorded, Error>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Recorded {
+    Query {
+        results: Vec<std::result::Result<Vec<Value>, Error>>,
+        stats: Vec<RecordedStats>,
+    },
+    Other(Value),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedStats {
+    execution_time: Option<Duration>,
+    ok: bool,
+}
+
+impl Entry {
+    fn new(method: Method, params: Vec<Value>, response: &Result<DbResponse>) -> Self {
+        let response = match response {
+            Ok(DbResponse::Query(response)) => Ok(Recorded::Query {
+                results: response.results.clone(),
+                stats: response
+                    .stats
+                    .iter()
+                    .map(|stats| RecordedStats {
+                        execution_time: stats.execution_time,
+                        ok: stats.status == Status::Ok,
+                    })
+                    .collect(),
+            }),
+            Ok(DbResponse::Other(value)) => Ok(Recorded::Other(value.clone())),
+            Err(error) => Err(error.clone()),
+        };
+        Self {
+            method,
+            params,
+            response,
+        }
+    }
+
+    fn into_response(self) -> Result<DbResponse> {
+        match self.response? {
+            Recorded::Query { results, stats } => Ok(DbResponse::Query(QueryResponse {
+                results,
+                stats: stats
+                    .into_iter()
+                    .map(|stats| Stats {
+                        execution_time: stats.execution_time,
+                        status: match stats.ok {
+                            true => Status::Ok,
+                            false => Status::Err,
+                        },
+                    })
+                    .collect(),
+            })),
+            Recorded::Other(value) => Ok(DbResponse::Other(value)),
+        }
+    }
+}
+
+/// Loads a fixture file into a mock server that replays its responses
+pub fn replay(path: impl AsRef<Path>) -> Result<MockServer> {
+    let server = MockServer::new();
+    let bytes = fs::read(path)?;
+    let mut bytes = bytes.as_slice();
+    while !bytes.is_empty() {
+        let entry: Entry = serde_pack::from_read(&mut bytes)?;
+        let expectation = server
+            .expect(entry.method)
+            .with_params(entry.params.clone());
+        match entry.into_response() {
+            Ok(response) => expectation.returns(response),
+            Err(error) => expectation.fails(error),
+        }
+    }
+    Ok(server)
+}
+
+impl<C> Connection for Client<C>
+where
+    C: Connection<Response = Result<DbResponse>>,
+{
+    type Request = (Method, Param);
+    type Response = Result<DbResponse>;
+
+    fn new(method: Method) -> Self {
+        Self {
+            method,
+            conn: PhantomData,
+        }
+    }
+
+    fn connect(
+        mut address: ServerAddrs,
+        capacity: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
+        Box::pin(async move {
+            let path = match address.harness.take() {
+                Some(Harness::Record(path)) => path,
+                _ => {
+                    return Err(ErrorKind::InvalidParams
+                        .with_message("a fixture file is required to record a connection"));
+                }
+            };
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(true)
+                .write(true)
+                .open(&path)?;
+
+            let request_timeout = address.config.request_timeout;
+            let inner = C::connect(address, capacity).await?;
+            let events = inner.router.extract()?.events.clone();
+
+            let (route_tx, route_rx) = match capacity {
+                0 => flume::unbounded(),
+                capacity => flume::bounded(capacity),
+            };
+
+            router(inner, file, route_rx);
+
+            Ok(Surreal {
+                router: OnceCell::with_value(Arc::new(Router {
+                    conn: PhantomData,
+                    sender: route_tx,
+                    timeout: request_timeout,
+                    events,
+                    requests: Requests::new(),
+                    session: Session::new(),
+                    owner: None,
+                    shutdown: None,
+                    #[cfg(feature = "ws")]
+                    last_id: AtomicI64::new(0),
+                    #[cfg(feature = "ws")]
+                    cancelled: None,
+                })),
+            })
+        })
+    }
+
+    fn send<'r>(
+        &'r mut self,
+        router: &'r Router<Self>,
+        param: Param,
+    ) -> Pin<Box<dyn Future<Output = Result<Receiver<Self::Response>>> + Send + Sync + 'r>> {
+        Box::pin(async move {
+            let (sender, receiver) = flume::bounded(1);
+            let route = Route {
+                request: (self.method, param),
+                response: sender,
+            };
+            router.sender.send_async(Some(route)).await?;
+            Ok(receiver)
+        })
+    }
+
+    fn recv<R>(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<R>> + Send + Sync + '_>>
+    where
+        R: DeserializeOwned,
+    {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Other(value) => from_value(&value),
+                DbResponse::Query(..) => unreachable!(),
+            }
+        })
+    }
+
+    fn recv_query(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<QueryResponse>> + Send + Sync + '_>> {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Query(response) => Ok(response),
+                DbResponse::Other(..) => unreachable!(),
+            }
+        })
+    }
+}
+
+fn router<C>(inner: Surreal<C>, mut file: File, route_rx: Receiver<Option<RecordRoute>>)
+where
+    C: Connection<Response = Result<DbResponse>>,
+{
+    tokio::spawn(async move {
+        let mut stream = route_rx.into_stream();
+
+        while let Some(Some(route)) = stream.next().await {
+            let (method, param) = route.request;
+            let params = param.query.clone();
+            let response = forward(&inner, method, param).await;
+            let entry = Entry::new(method, params, &response);
+            if let Err(error) = write_entry(&mut file, &entry) {
+                tracing::error!("failed to record `{}` request; {error}", method.as_str());
+            }
+            let _ = route.response.into_send_async(response).await;
+        }
+    });
+}
+
+async fn forward<C>(inner: &Surreal<C>, method: Method, param: Param) -> Result<DbResponse>
+where
+    C: Connection<Response = Result<DbResponse>>,
+{
+    let router = inner.router.extract()?;
+    let mut conn = C::new(method);
+    let rx = conn.send(router, param).await?;
+    rx.into_recv_async().await?
+}
+
+fn write_entry(file: &mut File, entry: &Entry) -> Result<()> {
+    let bytes = {
+        let _guard = InternalSerialization::begin();
+        serde_pack::to_vec(entry)?
+    };
+    file.write_all(&bytes)?;
+    file.flush()?;
+    Ok(())
+}
@@ -90,26 +90,60 @@
 //! }
 //! ```
 
-#[cfg(not(any(feature = "http", feature = "ws")))]
-compile_error!("Either feature \"http\" or \"ws\" must be enabled for this crate.");
+#[cfg(not(any(feature = "http", feature = "ws", feature = "mem", feature = "rocksdb")))]
+compile_error!(
+    "Either feature \"http\", \"ws\", \"mem\" or \"rocksdb\" must be enabled for this crate."
+);
 
 mod err;
-
+mod events;
+mod response;
+mod scope;
+mod session;
+
+#[cfg(all(feature = "test-util", not(target_arch = "wasm32")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "test-util", not(target_arch = "wasm32"))))
+)]
+pub mod fixture;
 pub mod method;
-
-#[cfg(any(feature = "http", feature = "ws"))]
-#[cfg_attr(docsrs, doc(cfg(any(feature = "http", feature = "ws"))))]
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod mock;
+
+#[cfg(any(feature = "http", feature = "ws", feature = "mem", feature = "rocksdb"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "http", feature = "ws", feature = "mem", feature = "rocksdb")))
+)]
 pub mod net;
 pub mod param;
-#[cfg(any(feature = "http", feature = "ws"))]
-#[cfg_attr(docsrs, doc(cfg(any(feature = "http", feature = "ws"))))]
+#[cfg(any(feature = "http", feature = "ws", feature = "mem", feature = "rocksdb"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "http", feature = "ws", feature = "mem", feature = "rocksdb")))
+)]
 pub mod protocol;
 
 pub use err::Error;
 pub use err::ErrorKind;
-
+pub use events::ConnectionEvent;
+pub use events::ConnectionEvents;
+pub use response::Response;
+pub use response::Stats;
+pub use response::Status;
+
+use crate::events::Events;
+use crate::param::Config;
+use crate::param::Jwt;
+use crate::param::Level;
+use crate::param::Options;
 use crate::param::ServerAddrs;
 use crate::param::ToServerAddrs;
+use crate::session::is_auth_failure;
+use crate::session::Refresh;
+use crate::session::Session;
 use flume::Receiver;
 use flume::Sender;
 use method::Method;
@@ -117,6 +151,7 @@ use once_cell::sync::OnceCell;
 use semver::BuildMetadata;
 use semver::VersionReq;
 use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
 use std::fmt::Debug;
 use std::future::Future;
 use std::future::IntoFuture;
@@ -127,14 +162,12 @@ use std::sync::atomic::AtomicI64;
 #[cfg(feature = "ws")]
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use surrealdb::sql::Value;
+use std::sync::Mutex;
+use std::time::Duration;
 
 /// Result type returned by the client
 pub type Result<T> = std::result::Result<T, Error>;
 
-/// Response type returned by the `query` method
-pub type Response = Vec<Result<Vec<Value>>>;
-
 const SUPPORTED_VERSIONS: (&str, &str) = (">=1.0.0-beta.8, <2.0.0", "20221030.c12a1cc");
 
 /// Connection trait implemented by supported protocols
@@ -185,8 +218,30 @@ pub trait Connection: Sized + Send + Sync + 'static {
         R: DeserializeOwned,
     {
         Box::pin(async move {
-            let rx = self.send(router, param).await?;
-            self.recv(rx).await
+            let _request = router.requests.start()?;
+            let (total, each) = router.timeouts(&param);
+            let request = async {
+                let retry = router
+                    .prepare_session()
+                    .await
+                    .map(|generation| (generation, param.clone()));
+                let future = async {
+                    let rx = self.send(router, param).await?;
+                    self.recv(rx).await
+                };
+                match (with_timeout(each, future).await, retry) {
+                    (Err(error), Some((generation, param))) if is_auth_failure(&error) => {
+                        router.refresh_session(generation).await?;
+                        let future = async {
+                            let rx = self.send(router, param).await?;
+                            self.recv(rx).await
+                        };
+                        with_timeout(each, future).await
+                    }
+                    (result, _) => result,
+                }
+            };
+            with_timeout(total, request).await
         })
     }
 
@@ -197,8 +252,30 @@ pub trait Connection: Sized + Send + Sync + 'static {
         param: param::Param,
     ) -> Pin<Box<dyn Future<Output = Result<Response>> + Send + Sync + 'r>> {
         Box::pin(async move {
-            let rx = self.send(router, param).await?;
-            self.recv_query(rx).await
+            let _request = router.requests.start()?;
+            let (total, each) = router.timeouts(&param);
+            let request = async {
+                let retry = router
+                    .prepare_session()
+                    .await
+                    .map(|generation| (generation, param.clone()));
+                let future = async {
+                    let rx = self.send(router, param).await?;
+                    self.recv_query(rx).await
+                };
+                match (with_timeout(each, future).await, retry) {
+                    (Err(error), Some((generation, param))) if is_auth_failure(&error) => {
+                        router.refresh_session(generation).await?;
+                        let future = async {
+                            let rx = self.send(router, param).await?;
+                            self.recv_query(rx).await
+                        };
+                        with_timeout(each, future).await
+                    }
+                    (result, _) => result,
+                }
+            };
+            with_timeout(total, request).await
         })
     }
 }
@@ -209,6 +286,7 @@ pub struct Connect<'r, C: Connection, Response> {
     router: Option<&'r OnceCell<Arc<Router<C>>>>,
     address: Result<ServerAddrs>,
     capacity: usize,
+    config: Config,
     client: PhantomData<C>,
     response_type: PhantomData<Response>,
 }
@@ -248,6 +326,35 @@ where
         self.capacity = capacity;
         self
     }
+
+    /// Sets the timeouts, keep-alive and reconnect behaviour of the connection
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> surrealdb_rs::Result<()> {
+    /// use std::time::Duration;
+    /// use surrealdb_rs::param::Config;
+    /// use surrealdb_rs::param::Reconnect;
+    /// use surrealdb_rs::protocol::Ws;
+    /// use surrealdb_rs::Surreal;
+    ///
+    /// let config = Config::new()
+    ///     .request_timeout(Duration::from_secs(30))
+    ///     .reconnect(Reconnect::never());
+    ///
+    /// let client = Surreal::connect::<Ws>("localhost:8000")
+    ///     .with_config(config)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
 }
 
 impl<'r, Client> IntoFuture for Connect<'r, Client, Surreal<Client>>
@@ -259,8 +366,10 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            let client = Client::connect(self.address?, self.capacity).await?;
-            client.check_server_version();
+            let (client, strict) = connect(self.address, self.capacity, self.config).await?;
+            if !strict {
+                client.spawn_version_check();
+            }
             Ok(client)
         })
     }
@@ -277,10 +386,8 @@ where
         Box::pin(async move {
             match self.router {
                 Some(router) => {
-                    let option = Client::connect(self.address?, self.capacity)
-                        .await?
-                        .router
-                        .into_inner();
+                    let (client, _) = connect(self.address, self.capacity, self.config).await?;
+                    let option = client.router.into_inner();
                     match option {
                         Some(client) => {
                             let _res = router.set(client);
@@ -295,6 +402,45 @@ where
     }
 }
 
+/// Connects to the server and applies the settings given in the endpoint
+///
+/// Also returns whether the server version was already checked.
+async fn connect<C>(
+    address: Result<ServerAddrs>,
+    capacity: usize,
+    config: Config,
+) -> Result<(Surreal<C>, bool)>
+where
+    C: Connection,
+{
+    let mut address = address?;
+    let options = address.take_options()?;
+    address.config = config;
+    let strict = options.strict;
+    let timeout = options.timeout.or(config.connect_timeout);
+    let future = async move {
+        let client = C::connect(address, capacity).await?;
+        client.setup(options).await?;
+        Ok(client)
+    };
+    let client = with_timeout(timeout, future).await?;
+    Ok((client, strict))
+}
+
+/// Fails with `ErrorKind::Timeout` if the future doesn't complete in time
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, future).await {
+            Ok(result) => result,
+            Err(..) => Err(ErrorKind::Timeout.with_message(format!("timed out after {timeout:?}"))),
+        },
+        None => future.await,
+    }
+}
+
 #[derive(Debug)]
 struct Route<A, R> {
     request: A,
@@ -306,17 +452,174 @@ struct Route<A, R> {
 pub struct Router<C: Connection> {
     conn: PhantomData<C>,
     sender: Sender<Option<Route<C::Request, C::Response>>>,
+    timeout: Option<Duration>,
+    events: Arc<Events>,
+    requests: Requests,
+    session: Session,
+    /// The router of the client whose connection this session shares, if this is a session
+    owner: Option<Arc<Router<C>>>,
+    /// Tells the client that closes the connection whether it shut down cleanly
+    shutdown: Option<Receiver<Result<()>>>,
     #[cfg(feature = "ws")]
     last_id: AtomicI64,
+    /// Tells the WebSocket router about requests whose callers stopped waiting
+    #[cfg(feature = "ws")]
+    cancelled: Option<Sender<i64>>,
 }
 
 impl<C> Router<C>
 where
     C: Connection,
 {
+    /// The timeouts of a request, for the whole of it and for each step it takes
+    ///
+    /// A timeout set on the request covers everything it does, including refreshing the
+    /// session. Otherwise the client-wide default applies to each step.
+    fn timeouts(&self, param: &param::Param) -> (Option<Duration>, Option<Duration>) {
+        match param.timeout {
+            Some(timeout) => (Some(timeout), None),
+            None => (None, self.timeout),
+        }
+    }
+
+    /// The router of the client that owns the connection
+    fn connection(&self) -> &Self {
+        self.owner.as_deref().unwrap_or(self)
+    }
+
+    /// Creates a session that shares the connection of this router
+    fn new_session(self: &Arc<Self>) -> Self {
+        let connection = self.connection();
+        Self {
+            conn: PhantomData,
+            sender: connection.sender.clone(),
+            timeout: connection.timeout,
+            events: connection.events.clone(),
+            requests: Requests::new(),
+            session: Session::new(),
+            owner: Some(match &self.owner {
+                Some(owner) => owner.clone(),
+                None => self.clone(),
+            }),
+            shutdown: None,
+            #[cfg(feature = "ws")]
+            last_id: AtomicI64::new(0),
+            #[cfg(feature = "ws")]
+            cancelled: connection.cancelled.clone(),
+        }
+    }
+
+    /// Fails requests that sessions can't make, since they share the sign-in, live queries
+    /// and context of the connection
+    fn reject_session(&self, method: &str) -> Result<&Self> {
+        match self.owner {
+            None => Ok(self),
+            Some(..) => Err(ErrorKind::MethodNotSupported.with_message(format!(
+                "`{method}` is not supported by sessions, only by the client they were created from"
+            ))),
+        }
+    }
+
+    /// Gets a new token if the current one is about to expire
+    ///
+    /// Returns the generation of the session if requests that fail authentication can be
+    /// retried after refreshing it. Requests are only retried if the token expires, since
+    /// they have to hold on to a copy of their parameters for that.
+    async fn prepare_session(&self) -> Option<u64> {
+        // the requests that refresh the session are never retried themselves
+        if REFRESHING.try_with(|_| ()).is_ok() {
+            return None;
+        }
+        // sessions share the sign-in of the client they were created from
+        let router = self.connection();
+        if router.session.expires_soon() {
+            if let Err(error) = router.refresh_session(router.session.generation()).await {
+                tracing::trace!("Failed to refresh the session; {error}");
+            }
+        }
+        if !router.session.expires() {
+            return None;
+        }
+        router
+            .session
+            .refresh()
+            .map(|_| router.session.generation())
+    }
+
+    /// Signs in again, or authenticates with a token from the refresh callback
+    ///
+    /// Nothing is done if the session was already refreshed since `generation`.
+    async fn refresh_session(&self, generation: u64) -> Result<()> {
+        let router = self.connection();
+        let _guard = router.session.lock().await;
+        if router.session.generation() != generation {
+            return Ok(());
+        }
+        let refresh = match router.session.refresh() {
+            Some(refresh) => refresh,
+            None => return Ok(()),
+        };
+        let future = async {
+            match refresh {
+                Refresh::Signin(credentials) => {
+                    let mut conn = C::new(Method::Signin);
+                    let value: JsonValue = conn
+                        .execute(router, param::Param::new(vec![credentials]))
+                        .await?;
+                    let token = serde_json::from_value::<Jwt>(value).ok();
+                    router.session.refreshed(token.as_ref()).await;
+                }
+                Refresh::Callback(callback) => {
+                    let token = callback().await?;
+                    let mut conn = C::new(Method::Authenticate);
+                    conn.execute::<()>(router, param::Param::new(vec![token.clone().into()]))
+                        .await?;
+                    router.session.refreshed(Some(&token)).await;
+                }
+            }
+            Ok(())
+        };
+        REFRESHING.scope((), future).await
+    }
+
     #[cfg(feature = "ws")]
     fn next_id(&self) -> i64 {
-        self.last_id.fetch_add(1, Ordering::SeqCst)
+        // the sessions sharing a connection also share its request IDs
+        self.connection().last_id.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// Keeps track of the requests that are waiting for a response
+#[derive(Debug)]
+struct Requests {
+    /// Cloned for each request, taken out once the connection starts closing
+    open: Mutex<Option<Sender<()>>>,
+    /// Disconnects once all requests are done and the connection is closing
+    done: Receiver<()>,
+}
+
+impl Requests {
+    fn new() -> Self {
+        let (sender, receiver) = flume::bounded(0);
+        Self {
+            open: Mutex::new(Some(sender)),
+            done: receiver,
+        }
+    }
+
+    /// Registers a new request, which is done once the returned guard is dropped
+    fn start(&self) -> Result<Sender<()>> {
+        match &*self.open.lock().unwrap() {
+            Some(sender) => Ok(sender.clone()),
+            None => Err(connection_closed()),
+        }
+    }
+
+    /// Stops accepting new requests and waits for those in flight to finish
+    async fn close(&self) {
+        self.open.lock().unwrap().take();
+        // nothing is ever sent, this only returns once all senders are dropped
+        let _res = self.done.recv_async().await;
     }
 }
 
@@ -325,7 +628,11 @@ where
     C: Connection,
 {
     fn drop(&mut self) {
-        let _res = self.sender.send(None);
+        // the connection stays open as long as a session shares it
+        if self.owner.is_none() {
+            let _res = self.sender.send(None);
+            self.events.emit(ConnectionEvent::Closed);
+        }
     }
 }
 
@@ -339,22 +646,33 @@ impl<C> Surreal<C>
 where
     C: Connection,
 {
-    fn check_server_version(&self) {
+    /// Checks that the client supports the version of the server
+    async fn check_server_version(&self) -> Result<()> {
+        let (versions, build_meta) = SUPPORTED_VERSIONS;
+        // invalid version requirements should be caught during development
+        let req = VersionReq::parse(versions).expect("valid supported versions");
+        let build_meta = BuildMetadata::new(build_meta).expect("valid supported build metadata");
+        let version = self.version().await?;
+        let server_build = &version.build;
+        if !req.matches(&version) {
+            return Err(ErrorKind::VersionMismatch.with_message(format!(
+                "server version `{version}` does not match the range supported by the client `{versions}`"
+            )));
+        } else if server_build < &build_meta {
+            return Err(ErrorKind::VersionMismatch.with_message(format!(
+                "server build `{server_build}` is older than the minimum supported build `{build_meta}`"
+            )));
+        }
+        Ok(())
+    }
+
+    fn spawn_version_check(&self) {
         let conn = self.clone();
         tokio::spawn(async move {
-            let (versions, build_meta) = SUPPORTED_VERSIONS;
-            // invalid version requirements should be caught during development
-            let req = VersionReq::parse(versions).expect("valid supported versions");
-            let build_meta =
-                BuildMetadata::new(build_meta).expect("valid supported build metadata");
-            match conn.version().await {
-                Ok(version) => {
-                    let server_build = &version.build;
-                    if !req.matches(&version) {
-                        tracing::warn!("server version `{version}` does not match the range supported by the client `{versions}`");
-                    } else if server_build < &build_meta {
-                        tracing::warn!("server build `{server_build}` is older than the minimum supported build `{build_meta}`");
-                    }
+            match conn.check_server_version().await {
+                Ok(()) => {}
+                Err(error) if error.kind() == ErrorKind::VersionMismatch => {
+                    tracing::warn!("{error}");
                 }
                 Err(error) => {
                     tracing::trace!("failed to lookup the server version; {error:?}");
@@ -362,6 +680,45 @@ where
             }
         });
     }
+
+    /// Applies the settings given in a connection string
+    async fn setup(&self, options: Options) -> Result<()> {
+        if options.strict {
+            self.check_server_version().await?;
+        }
+        if let Some(auth) = options.auth {
+            let namespace = options.namespace.as_deref().unwrap_or_default();
+            let database = options.database.as_deref().unwrap_or_default();
+            let username = &auth.username;
+            let password = &auth.password;
+            match auth.level {
+                Level::Root => self.signin(param::Root { username, password }).await?,
+                Level::Namespace => {
+                    let _: param::Jwt = self
+                        .signin(param::NameSpace {
+                            namespace,
+                            username,
+                            password,
+                        })
+                        .await?;
+                }
+                Level::Database => {
+                    let _: param::Jwt = self
+                        .signin(param::Database {
+                            namespace,
+                            database,
+                            username,
+                            password,
+                        })
+                        .await?;
+                }
+            }
+        }
+        if let (Some(ns), Some(db)) = (options.namespace, options.database) {
+            self.use_ns(ns).use_db(db).await?;
+        }
+        Ok(())
+    }
 }
 
 impl<C> Clone for Surreal<C>
@@ -384,11 +741,52 @@ where
     fn connect<P>(&self, address: impl ToServerAddrs<P, Client = C>) -> Connect<C, ()>;
 }
 
+/// Exposes a `timeout` method on the requests the client makes
+///
+/// The timeout replaces the client-wide default set with
+/// [`Config::request_timeout`](param::Config::request_timeout) for this request. Requests
+/// the client makes by itself while serving it, for example to refresh an expiring token,
+/// keep their own timeouts.
+///
+/// This trait is sealed: it's implemented for the request futures of this crate only.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb_rs::Result<()> {
+/// use std::time::Duration;
+/// use surrealdb_rs::protocol::Ws;
+/// use surrealdb_rs::Surreal;
+/// use surrealdb_rs::WithTimeout;
+///
+/// let client = Surreal::connect::<Ws>("localhost:8000").await?;
+/// client.use_ns("namespace").use_db("database").await?;
+/// let response = client
+///     .query("SELECT * FROM person")
+///     .timeout(Duration::from_secs(5))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait WithTimeout: method::Sealed + Sized {
+    /// Fails the request with [`ErrorKind::Timeout`] if it doesn't complete in time
+    #[must_use]
+    fn timeout(self, duration: Duration) -> Self;
+}
+
+tokio::task_local! {
+    /// Set while the client gets a new token
+    static REFRESHING: ();
+}
+
 trait ExtractRouter<C>
 where
     C: Connection,
 {
     fn extract(&self) -> Result<&Router<C>>;
+
+    fn extract_owned(&self) -> Result<Arc<Router<C>>>;
 }
 
 impl<C> ExtractRouter<C> for OnceCell<Arc<Router<C>>>
@@ -399,8 +797,17 @@ where
         let router = self.get().ok_or_else(connection_uninitialised)?;
         Ok(router)
     }
+
+    fn extract_owned(&self) -> Result<Arc<Router<C>>> {
+        let router = self.get().ok_or_else(connection_uninitialised)?;
+        Ok(router.clone())
+    }
 }
 
 fn connection_uninitialised() -> Error {
     ErrorKind::ConnectionUninitialized.with_message("connection uninitialized")
 }
+
+fn connection_closed() -> Error {
+    ErrorKind::ConnectionClosed.with_message("the connection was closed")
+}
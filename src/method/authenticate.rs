@@ -7,11 +7,13 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// An authentication future
 #[derive(Debug)]
 pub struct Authenticate<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) token: Jwt,
 }
 
@@ -24,9 +26,15 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
+            let router = self.router?;
             let mut conn = Client::new(Method::Authenticate);
-            conn.execute(self.router?, Param::new(vec![self.token.into()]))
-                .await
+            conn.execute::<()>(
+                router,
+                Param::new(vec![self.token.clone().into()]).timeout(self.timeout),
+            )
+            .await?;
+            router.session.authenticated(Some(&self.token)).await;
+            Ok(())
         })
     }
 }
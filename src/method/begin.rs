@@ -1,13 +1,12 @@
-use crate::method::Cancel;
-use crate::method::Commit;
+use crate::method::Transaction;
 use crate::Connection;
+use crate::ExtractRouter;
 use crate::Result;
 use crate::Surreal;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::future::IntoFuture;
-use std::ops::Deref;
 use std::pin::Pin;
-use surrealdb::sql::statements::BeginStatement;
 
 /// A beginning of a transaction
 #[derive(Debug)]
@@ -24,46 +23,15 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            self.client.query(BeginStatement).await?;
+            // statements are only sent on commit but there is no point
+            // in buffering them if the client is not connected
+            self.client.router.extract()?;
             Ok(Transaction {
                 client: self.client,
+                statements: Vec::new(),
+                bindings: Ok(BTreeMap::new()),
+                results: 0,
             })
         })
     }
 }
-
-/// An ongoing transaction
-#[derive(Debug)]
-pub struct Transaction<C: Connection> {
-    client: Surreal<C>,
-}
-
-impl<C> Transaction<C>
-where
-    C: Connection,
-{
-    /// Creates a commit future
-    pub fn commit(self) -> Commit<C> {
-        Commit {
-            client: self.client,
-        }
-    }
-
-    /// Creates a cancel future
-    pub fn cancel(self) -> Cancel<C> {
-        Cancel {
-            client: self.client,
-        }
-    }
-}
-
-impl<C> Deref for Transaction<C>
-where
-    C: Connection,
-{
-    type Target = Surreal<C>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.client
-    }
-}
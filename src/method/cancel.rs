@@ -1,28 +1,26 @@
+use crate::method::Transaction;
 use crate::Connection;
 use crate::Result;
-use crate::Surreal;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
-use surrealdb::sql::statements::CancelStatement;
 
 /// A transaction cancellation future
 #[derive(Debug)]
 pub struct Cancel<C: Connection> {
-    pub(crate) client: Surreal<C>,
+    pub(crate) transaction: Transaction<C>,
 }
 
 impl<C> IntoFuture for Cancel<C>
 where
     C: Connection,
 {
-    type Output = Result<Surreal<C>>;
+    type Output = Result<()>;
     type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
 
     fn into_future(self) -> Self::IntoFuture {
-        Box::pin(async move {
-            self.client.query(CancelStatement).await?;
-            Ok(self.client)
-        })
+        // nothing has been sent to the server yet so there is nothing to roll back
+        drop(self.transaction);
+        Box::pin(async { Ok(()) })
     }
 }
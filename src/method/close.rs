@@ -0,0 +1,71 @@
+use crate::with_timeout;
+use crate::Connection;
+use crate::ConnectionEvent;
+use crate::ErrorKind;
+use crate::Result;
+use crate::Router;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// A future that closes the connection once the requests in flight are done
+#[derive(Debug)]
+pub struct Close<'r, C: Connection> {
+    pub(super) router: Result<&'r Router<C>>,
+    pub(super) deadline: Duration,
+}
+
+impl<C> Close<'_, C>
+where
+    C: Connection,
+{
+    /// Sets how long to wait for the requests in flight before closing anyway
+    ///
+    /// Defaults to 10 seconds.
+    #[must_use]
+    pub const fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+}
+
+impl<'r, Client> IntoFuture for Close<'r, Client>
+where
+    Client: Connection,
+{
+    type Output = Result<()>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let router = self.router?;
+            let deadline = self.deadline;
+            let drained = with_timeout(Some(deadline), async {
+                router.requests.close().await;
+                Ok(())
+            })
+            .await;
+            let timed_out = || {
+                ErrorKind::Timeout.with_message(format!(
+                    "closed the connection while requests were still waiting for a response after {deadline:?}"
+                ))
+            };
+            // a session only stops taking requests, the connection belongs to its client
+            if router.owner.is_some() {
+                return drained.map_err(|_| timed_out());
+            }
+            // the router kills live queries and sends a close frame before it shuts down,
+            // unless it already stopped because it couldn't reconnect
+            let _res = router.sender.send_async(None).await;
+            let shutdown = match &router.shutdown {
+                // a router that already stopped has nothing left to report
+                Some(shutdown_rx) => shutdown_rx.recv_async().await.unwrap_or(Ok(())),
+                None => Ok(()),
+            };
+            router.events.emit(ConnectionEvent::Closed);
+            drained.map_err(|_| timed_out())?;
+            shutdown
+        })
+    }
+}
@@ -1,28 +1,54 @@
+use crate::method::Transaction;
+use crate::param::Param;
 use crate::Connection;
+use crate::ExtractRouter;
+use crate::Response;
 use crate::Result;
-use crate::Surreal;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
+use surrealdb::sql;
+use surrealdb::sql::statements::BeginStatement;
 use surrealdb::sql::statements::CommitStatement;
+use surrealdb::sql::Statement;
+use surrealdb::sql::Statements;
 
 /// A transaction commit future
 #[derive(Debug)]
 pub struct Commit<C: Connection> {
-    pub(crate) client: Surreal<C>,
+    pub(crate) transaction: Transaction<C>,
+    pub(crate) timeout: Option<Duration>,
 }
 
-impl<C> IntoFuture for Commit<C>
+impl<Client> IntoFuture for Commit<Client>
 where
-    C: Connection,
+    Client: Connection,
 {
-    type Output = Result<Surreal<C>>;
+    type Output = Result<Response>;
     type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            self.client.query(CommitStatement).await?;
-            Ok(self.client)
+            let Transaction {
+                client,
+                statements: buffered,
+                bindings,
+                ..
+            } = self.transaction;
+            let mut bindings = bindings?;
+            let mut statements = Vec::with_capacity(buffered.len() + 2);
+            statements.push(Statement::Begin(BeginStatement));
+            for (index, buffered) in buffered.into_iter().enumerate() {
+                statements.extend(buffered.into_statements(index, &mut bindings)?);
+            }
+            statements.push(Statement::Commit(CommitStatement));
+            let mut param = vec![sql::Query(Statements(statements)).to_string().into()];
+            if !bindings.is_empty() {
+                param.push(bindings.into());
+            }
+            let param = Param::new(param).timeout(self.timeout);
+            client.router.extract()?.execute_query(param).await
         })
     }
 }
@@ -1,5 +1,5 @@
 use crate::method::Method;
-use crate::param::from_json;
+use crate::param::to_value;
 use crate::param::DbResource;
 use crate::param::Param;
 use crate::param::Range;
@@ -8,11 +8,11 @@ use crate::Result;
 use crate::Router;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use serde_json::json;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Id;
 
 /// A content future
@@ -21,6 +21,7 @@ use surrealdb::sql::Id;
 #[derive(Debug)]
 pub struct Content<'r, C: Connection, D, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) method: Method,
     pub(super) resource: Result<DbResource>,
     pub(super) range: Option<Range<Id>>,
@@ -39,8 +40,7 @@ where
             Some(range) => resource.with_range(range)?,
             None => resource.into(),
         };
-        let content = json!(self.content);
-        let param = Param::new(vec![param, from_json(content)]);
+        let param = Param::new(vec![param, to_value(self.content)?]).timeout(self.timeout);
         Ok((self.router?, self.method, param))
     }
 }
@@ -58,8 +58,7 @@ where
         let result = self.split();
         Box::pin(async move {
             let (router, method, param) = result?;
-            let mut conn = Client::new(method);
-            conn.execute(router, param).await
+            router.execute(method, param).await
         })
     }
 }
@@ -11,11 +11,13 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A record create future
 #[derive(Debug)]
 pub struct Create<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) resource: Result<DbResource>,
     pub(super) response_type: PhantomData<R>,
 }
@@ -28,8 +30,11 @@ where
     where
         T: DeserializeOwned,
     {
-        let mut conn = Client::new(Method::Create);
-        conn.execute(self.router?, Param::new(vec![self.resource?.into()]))
+        self.router?
+            .execute(
+                Method::Create,
+                Param::new(vec![self.resource?.into()]).timeout(self.timeout),
+            )
             .await
     }
 }
@@ -73,6 +78,7 @@ macro_rules! create_methods {
             {
                 Content {
                     router: self.router,
+                    timeout: self.timeout,
                     method: Method::Create,
                     resource: self.resource,
                     range: None,
@@ -9,12 +9,14 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Id;
 
 /// A record delete future
 #[derive(Debug)]
 pub struct Delete<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) resource: Result<DbResource>,
     pub(super) range: Option<Range<Id>>,
     pub(super) response_type: PhantomData<R>,
@@ -30,8 +32,12 @@ where
             Some(range) => resource.with_range(range)?,
             None => resource.into(),
         };
-        let mut conn = Client::new(Method::Delete);
-        conn.execute(self.router?, Param::new(vec![param])).await
+        self.router?
+            .execute(
+                Method::Delete,
+                Param::new(vec![param]).timeout(self.timeout),
+            )
+            .await
     }
 }
 
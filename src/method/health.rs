@@ -6,11 +6,13 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A health check future
 #[derive(Debug)]
 pub struct Health<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
 }
 
 impl<'r, Client> IntoFuture for Health<'r, Client>
@@ -23,7 +25,8 @@ where
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async {
             let mut conn = Client::new(Method::Health);
-            conn.execute(self.router?, Param::new(Vec::new())).await
+            conn.execute(self.router?, Param::new(Vec::new()).timeout(self.timeout))
+                .await
         })
     }
 }
@@ -7,11 +7,13 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// An database import future
 #[derive(Debug)]
 pub struct Import<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) file: PathBuf,
 }
 
@@ -25,7 +27,8 @@ where
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async {
             let mut conn = Client::new(Method::Import);
-            conn.execute(self.router?, Param::file(self.file)).await
+            conn.execute(self.router?, Param::file(self.file).timeout(self.timeout))
+                .await
         })
     }
 }
@@ -6,11 +6,13 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A session invalidate future
 #[derive(Debug)]
 pub struct Invalidate<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
 }
 
 impl<'r, Client> IntoFuture for Invalidate<'r, Client>
@@ -22,8 +24,12 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async {
+            let router = self.router?;
             let mut conn = Client::new(Method::Invalidate);
-            conn.execute(self.router?, Param::new(Vec::new())).await
+            conn.execute::<()>(router, Param::new(Vec::new()).timeout(self.timeout))
+                .await?;
+            router.session.invalidated().await;
+            Ok(())
         })
     }
 }
@@ -6,12 +6,14 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Uuid;
 
 /// A live query kill future
 #[derive(Debug)]
 pub struct Kill<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) query_id: Uuid,
 }
 
@@ -25,8 +27,11 @@ where
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
             let mut conn = Client::new(Method::Kill);
-            conn.execute(self.router?, Param::new(vec![self.query_id.into()]))
-                .await
+            conn.execute(
+                self.router?,
+                Param::new(vec![self.query_id.into()]).timeout(self.timeout),
+            )
+            .await
         })
     }
 }
@@ -1,32 +1,149 @@
+use crate::method::Kill;
 use crate::method::Method;
+use crate::param::from_value;
+use crate::param::Notification;
 use crate::param::Param;
 use crate::Connection;
 use crate::Result;
 use crate::Router;
+use flume::r#async::RecvStream;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::fmt;
 use std::future::Future;
 use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::mem;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
 use surrealdb::sql::Uuid;
+use surrealdb::sql::Value;
 
 /// A live query future
 #[derive(Debug)]
-pub struct Live<'r, C: Connection> {
-    pub(super) router: Result<&'r Router<C>>,
+pub struct Live<C: Connection, R> {
+    pub(super) router: Result<Arc<Router<C>>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) table_name: String,
+    pub(super) response_type: PhantomData<R>,
 }
 
-impl<'r, Client> IntoFuture for Live<'r, Client>
+impl<Client, R> IntoFuture for Live<Client, R>
 where
     Client: Connection,
+    R: DeserializeOwned + Send + Sync + 'static,
 {
-    type Output = Result<Uuid>;
-    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+    type Output = Result<Stream<Client, R>>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
+            let router = self.router?;
+            let (sender, receiver) = flume::unbounded();
+            let param = Param::live(vec![self.table_name.into()], sender).timeout(self.timeout);
             let mut conn = Client::new(Method::Live);
-            conn.execute(self.router?, Param::new(vec![self.table_name.into()]))
-                .await
+            let query_id: Uuid = conn.execute(&router, param).await?;
+            Ok(Stream {
+                router,
+                query_id,
+                receiver: receiver.into_stream(),
+                response_type: PhantomData,
+            })
         })
     }
 }
+
+/// A stream of live query notifications
+///
+/// The live query is killed when the stream is dropped.
+///
+/// If the connection drops, the live query is registered again once the client
+/// reconnects and the stream yields an error of kind [`ErrorKind::NotificationsMissed`]
+/// to signal that notifications may have been lost in the meantime. The stream keeps
+/// going after such errors.
+///
+/// [`ErrorKind::NotificationsMissed`]: crate::ErrorKind::NotificationsMissed
+#[must_use = "streams do nothing unless you poll them"]
+pub struct Stream<C: Connection, R> {
+    router: Arc<Router<C>>,
+    query_id: Uuid,
+    receiver: RecvStream<'static, Result<Notification<Value>>>,
+    response_type: PhantomData<R>,
+}
+
+impl<C, R> Stream<C, R>
+where
+    C: Connection,
+{
+    /// Returns the ID of the live query backing this stream
+    pub fn query_id(&self) -> &Uuid {
+        &self.query_id
+    }
+}
+
+impl<C, R> futures::Stream for Stream<C, R>
+where
+    C: Connection,
+    R: DeserializeOwned + Unpin,
+{
+    type Item = Result<Notification<R>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx).map(|option| {
+            option.map(|result| {
+                let notification = result?;
+                let data = from_value(&notification.data)?;
+                Ok(Notification {
+                    query_id: notification.query_id,
+                    action: notification.action,
+                    data,
+                })
+            })
+        })
+    }
+}
+
+impl<C, R> fmt::Debug for Stream<C, R>
+where
+    C: Connection,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stream")
+            .field("query_id", &self.query_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C, R> Drop for Stream<C, R>
+where
+    C: Connection,
+{
+    fn drop(&mut self) {
+        let router = self.router.clone();
+        let query_id = mem::take(&mut self.query_id);
+        let kill = async move {
+            let kill = Kill {
+                router: Ok(&*router),
+                timeout: None,
+                query_id,
+            };
+            if let Err(error) = kill.await {
+                tracing::trace!("failed to kill live query; {error}");
+            }
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(kill);
+            }
+            Err(..) => {
+                tracing::trace!("no runtime available to kill live query");
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(kill);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::method::Method;
-use crate::param::from_json;
+use crate::param::to_value;
 use crate::param::DbResource;
 use crate::param::Param;
 use crate::param::Range;
@@ -8,17 +8,18 @@ use crate::Result;
 use crate::Router;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use serde_json::json;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Id;
 
 /// A merge future
 #[derive(Debug)]
 pub struct Merge<'r, C: Connection, D, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) resource: Result<DbResource>,
     pub(super) range: Option<Range<Id>>,
     pub(super) content: D,
@@ -36,8 +37,7 @@ where
             Some(range) => resource.with_range(range)?,
             None => resource.into(),
         };
-        let content = json!(self.content);
-        let param = Param::new(vec![param, from_json(content)]);
+        let param = Param::new(vec![param, to_value(self.content)?]).timeout(self.timeout);
         Ok((self.router?, Method::Merge, param))
     }
 }
@@ -55,8 +55,7 @@ where
         let result = self.split();
         Box::pin(async move {
             let (router, method, param) = result?;
-            let mut conn = Client::new(method);
-            conn.execute(router, param).await
+            router.execute(method, param).await
         })
     }
 }
@@ -3,14 +3,21 @@
 mod authenticate;
 mod begin;
 mod cancel;
+mod close;
 mod commit;
 mod content;
 mod create;
 mod delete;
-#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
 mod export;
 mod health;
-#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
 mod import;
 mod invalidate;
 mod kill;
@@ -18,10 +25,14 @@ mod live;
 mod merge;
 mod patch;
 mod query;
+mod restore_session;
 mod select;
 mod set;
 mod signin;
 mod signup;
+mod timeout;
+mod transaction;
+mod transaction_with;
 mod unset;
 mod update;
 mod use_ns;
@@ -31,36 +42,58 @@ mod version;
 mod tests;
 
 pub use authenticate::Authenticate;
-#[doc(hidden)] // Not supported yet
 pub use begin::Begin;
-#[doc(hidden)] // Not supported yet
-pub use begin::Transaction;
-#[doc(hidden)] // Not supported yet
 pub use cancel::Cancel;
-#[doc(hidden)] // Not supported yet
+pub use close::Close;
 pub use commit::Commit;
 pub use content::Content;
 pub use create::Create;
 pub use delete::Delete;
-#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
-#[cfg_attr(docsrs, doc(cfg(all(feature = "http", not(target_arch = "wasm32")))))]
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    )))
+)]
 pub use export::Export;
 pub use health::Health;
-#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
-#[cfg_attr(docsrs, doc(cfg(all(feature = "http", not(target_arch = "wasm32")))))]
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    )))
+)]
 pub use import::Import;
 pub use invalidate::Invalidate;
-#[doc(hidden)] // Not supported yet
 pub use kill::Kill;
-#[doc(hidden)] // Not supported yet
 pub use live::Live;
+pub use live::Stream;
 pub use merge::Merge;
 pub use patch::Patch;
 pub use query::Query;
+pub use restore_session::RestoreSession;
 pub use select::Select;
 pub use set::Set;
 pub use signin::Signin;
 pub use signup::Signup;
+pub(crate) use timeout::Sealed;
+pub use transaction::Handle;
+pub use transaction::Transaction;
+pub use transaction::TransactionCreate;
+pub use transaction::TransactionDelete;
+pub use transaction::TransactionSelect;
+pub use transaction::TransactionUpdate;
+pub use transaction_with::TransactionWith;
 pub use unset::Unset;
 pub use update::Update;
 pub use use_ns::UseNs;
@@ -68,23 +101,32 @@ pub use use_ns::UseNsDb;
 pub use version::Version;
 
 use crate::param;
-use crate::param::from_json;
+use crate::param::to_value;
 use crate::param::ToServerAddrs;
 use crate::Connect;
 use crate::Connection;
+use crate::ConnectionEvents;
 use crate::ExtractRouter;
+use crate::Result;
 use crate::StaticClient;
 use crate::Surreal;
 use once_cell::sync::OnceCell;
+use serde::Deserialize;
 use serde::Serialize;
-use serde_json::json;
+use std::future::Future;
 use std::marker::PhantomData;
-#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 use surrealdb::sql::Uuid;
 
 /// The query method
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Method {
     /// Sends an authentication token to the server
@@ -96,22 +138,38 @@ pub enum Method {
     /// Deletes a record from a table
     Delete,
     /// Exports a database
-    #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
-    #[cfg_attr(docsrs, doc(cfg(all(feature = "http", not(target_arch = "wasm32")))))]
+    #[cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            any(feature = "http", feature = "rocksdb"),
+            not(target_arch = "wasm32")
+        )))
+    )]
     Export,
     /// Checks the health of the server
     Health,
     /// Imports a database
-    #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
-    #[cfg_attr(docsrs, doc(cfg(all(feature = "http", not(target_arch = "wasm32")))))]
+    #[cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            any(feature = "http", feature = "rocksdb"),
+            not(target_arch = "wasm32")
+        )))
+    )]
     Import,
     /// Invalidates a session
     Invalidate,
     /// Kills a live query
-    #[doc(hidden)] // Not supported yet
     Kill,
     /// Starts a live query
-    #[doc(hidden)] // Not supported yet
     Live,
     /// Perfoms a patch update operation
     Patch,
@@ -141,10 +199,16 @@ impl Method {
             Method::Authenticate => "authenticate",
             Method::Create => "create",
             Method::Delete => "delete",
-            #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+            #[cfg(all(
+                any(feature = "http", feature = "rocksdb"),
+                not(target_arch = "wasm32")
+            ))]
             Method::Export => "export",
             Method::Health => "health",
-            #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+            #[cfg(all(
+                any(feature = "http", feature = "rocksdb"),
+                not(target_arch = "wasm32")
+            ))]
             Method::Import => "import",
             Method::Invalidate => "invalidate",
             Method::Kill => "kill",
@@ -173,6 +237,7 @@ where
             router: Some(&self.router),
             address: address.to_server_addrs(),
             capacity: 0,
+            config: Default::default(),
             client: PhantomData,
             response_type: PhantomData,
         }
@@ -259,14 +324,88 @@ where
             router: None,
             address: address.to_server_addrs(),
             capacity: 0,
+            config: Default::default(),
             client: PhantomData,
             response_type: PhantomData,
         }
     }
 
-    #[doc(hidden)] // Not supported yet
-    pub fn transaction(self) -> Begin<C> {
-        Begin { client: self }
+    /// Starts a transaction
+    ///
+    /// Statements issued through the transaction are buffered on the client and sent to the
+    /// server as a single block when the transaction is committed. Cancelling the transaction
+    /// discards them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(Serialize, Deserialize)]
+    /// # struct Account {
+    /// #     balance: f64,
+    /// # }
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let mut transaction = client.transaction().await?;
+    /// transaction
+    ///     .create(("account", "one"))
+    ///     .content(Account { balance: 135605.16 });
+    /// transaction
+    ///     .create(("account", "two"))
+    ///     .content(Account { balance: 91031.31 });
+    /// transaction
+    ///     .query("UPDATE account:one SET balance += $amount")
+    ///     .query("UPDATE account:two SET balance -= $amount")
+    ///     .bind("amount", 300.00);
+    /// let accounts = transaction.select("account").handle();
+    /// // one result per statement
+    /// let mut response = transaction.commit().await?;
+    /// let accounts: Vec<Account> = response.take(accounts)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transaction(&self) -> Begin<C> {
+        Begin {
+            client: self.clone(),
+        }
+    }
+
+    /// Runs a transaction built by a function
+    ///
+    /// The transaction is committed if the function returns `Ok` and cancelled if it returns
+    /// `Err`. If the server reports that the transaction conflicted with another one, the
+    /// whole function is run again after a backoff, up to 5 times by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let results = client
+    ///     .transaction_with(|mut transaction| async move {
+    ///         transaction
+    ///             .query("UPDATE account:one SET balance += $amount")
+    ///             .query("UPDATE account:two SET balance -= $amount")
+    ///             .bind("amount", 300.00);
+    ///         Ok::<_, surrealdb_rs::Error>(transaction)
+    ///     })
+    ///     .retries(10)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transaction_with<F>(&self, function: F) -> TransactionWith<C, F> {
+        TransactionWith {
+            client: self.clone(),
+            function,
+            retries: transaction_with::DEFAULT_RETRIES,
+        }
     }
 
     /// Switch to a specific namespace
@@ -290,6 +429,44 @@ where
         }
     }
 
+    /// Creates a session sharing the connection of this client
+    ///
+    /// A session has its own namespace, database and parameters, selected with
+    /// [`use_ns`](Self::use_ns) and [`set`](Self::set) as usual. Its queries and
+    /// record requests run in them without affecting the client or other sessions,
+    /// so one connection can serve several tenants at once. Until it selects its own
+    /// namespace and database, a session uses those of the connection, and its
+    /// parameters take precedence over the ones set on the connection.
+    ///
+    /// Sessions share the sign-in and live queries of the connection, so signing
+    /// in, up or out, authenticating, restoring sessions, refreshing tokens, live
+    /// queries, exports and imports fail on a session with
+    /// [`ErrorKind::MethodNotSupported`](crate::ErrorKind::MethodNotSupported).
+    /// Connect another client for a different user. Closing a session only waits for its
+    /// own requests; the connection stays open as long as the client or one of its
+    /// sessions is around.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let tenant = client.session()?;
+    /// tenant.use_ns("tenant_a").use_db("app").await?;
+    /// let people = tenant.query("SELECT * FROM person").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn session(&self) -> Result<Self> {
+        let router = self.router.extract_owned()?;
+        Ok(Self {
+            router: OnceCell::with_value(Arc::new(router.new_session())),
+        })
+    }
+
     /// Signs this connection up to a specific authentication scope
     ///
     /// # Examples
@@ -324,14 +501,22 @@ where
         credentials: impl param::Credentials<param::Signup, R>,
     ) -> Signup<C, R> {
         Signup {
-            router: self.router.extract(),
-            credentials: Ok(from_json(json!(credentials))),
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("signup")),
+            timeout: None,
+            credentials: to_value(credentials),
             response_type: PhantomData,
         }
     }
 
     /// Signs this connection in to a specific authentication scope
     ///
+    /// Shortly before the token expires, the client signs in again with the same credentials.
+    /// Requests that fail authentication because the token expired while they were in flight
+    /// are retried once after signing in again.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -364,8 +549,12 @@ where
         credentials: impl param::Credentials<param::Signin, R>,
     ) -> Signin<C, R> {
         Signin {
-            router: self.router.extract(),
-            credentials: Ok(from_json(json!(credentials))),
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("signin")),
+            timeout: None,
+            credentials: to_value(credentials),
             response_type: PhantomData,
         }
     }
@@ -386,7 +575,11 @@ where
     /// ```
     pub fn invalidate(&self) -> Invalidate<C> {
         Invalidate {
-            router: self.router.extract(),
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("invalidate")),
+            timeout: None,
         }
     }
 
@@ -407,11 +600,104 @@ where
     /// ```
     pub fn authenticate(&self, token: impl Into<param::Jwt>) -> Authenticate<C> {
         Authenticate {
-            router: self.router.extract(),
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("authenticate")),
+            timeout: None,
             token: token.into(),
         }
     }
 
+    /// Restores the session saved in a store and keeps saving it there whenever it changes
+    ///
+    /// The client authenticates with the saved token unless it has expired, switches to the
+    /// saved namespace and database and assigns the saved parameters again. Resolves to
+    /// `true` if the client is signed in with the saved token.
+    ///
+    /// Only the token returned by the server is saved, never the credentials given to
+    /// [`signin`](Self::signin). Signing in as the root user doesn't return a token, so
+    /// such sessions are restored without authentication.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// use surrealdb_rs::param::FileStore;
+    /// use surrealdb_rs::param::Scope;
+    /// # use serde::Serialize;
+    /// # #[derive(Serialize)]
+    /// # struct User<'a> {
+    /// #     email: &'a str,
+    /// #     pass: &'a str,
+    /// # }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let signed_in = client.restore_session(FileStore::new("session.bin")).await?;
+    /// if !signed_in {
+    ///     client
+    ///         .signin(Scope {
+    ///             namespace: "namespace",
+    ///             database: "database",
+    ///             scope: "user_scope",
+    ///             params: User {
+    ///                 email: "john.doe@example.com",
+    ///                 pass: "password123",
+    ///             },
+    ///         })
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_session(&self, store: impl param::SessionStore + 'static) -> RestoreSession<C> {
+        RestoreSession {
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("restore_session")),
+            timeout: None,
+            store: Arc::new(store),
+        }
+    }
+
+    /// Sets how the client gets a new token when the current one expires
+    ///
+    /// The client authenticates with the token the callback returns shortly before the
+    /// current one expires, and retries requests that fail authentication once if the token
+    /// expired while they were in flight. The callback
+    /// takes precedence over signing in again with the credentials given to
+    /// [`signin`](Self::signin).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # use surrealdb_rs::param::Jwt;
+    /// # async fn fetch_token() -> Result<Jwt> { unimplemented!() }
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// client.refresh_with(|| async { fetch_token().await })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn refresh_with<F, Fut>(&self, callback: F) -> Result<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<param::Jwt>> + Send + Sync + 'static,
+    {
+        let router = self.router.extract()?.reject_session("refresh_with")?;
+        router.session.set_callback(Arc::new(move || {
+            Box::pin(callback()) as Pin<Box<dyn Future<Output = Result<param::Jwt>> + Send + Sync>>
+        }));
+        Ok(())
+    }
+
     /// Assigns a value as a parameter for this connection
     ///
     /// # Examples
@@ -444,8 +730,9 @@ where
     pub fn set(&self, key: impl Into<String>, value: impl Serialize) -> Set<C> {
         Set {
             router: self.router.extract(),
+            timeout: None,
             key: key.into(),
-            value: Ok(from_json(json!(value))),
+            value: to_value(value),
         }
     }
 
@@ -481,6 +768,7 @@ where
     pub fn unset(&self, key: impl Into<String>) -> Unset<C> {
         Unset {
             router: self.router.extract(),
+            timeout: None,
             key: key.into(),
         }
     }
@@ -516,8 +804,9 @@ where
     pub fn query(&self, query: impl param::Query) -> Query<C> {
         Query {
             router: self.router.extract(),
+            timeout: None,
             query: vec![query.try_into_query()],
-            bindings: Default::default(),
+            bindings: Ok(Default::default()),
         }
     }
 
@@ -546,6 +835,7 @@ where
     pub fn select<R>(&self, resource: impl param::Resource<R>) -> Select<C, R> {
         Select {
             router: self.router.extract(),
+            timeout: None,
             resource: resource.into_db_resource(),
             range: None,
             response_type: PhantomData,
@@ -593,6 +883,7 @@ where
     pub fn create<R>(&self, resource: impl param::Resource<R>) -> Create<C, R> {
         Create {
             router: self.router.extract(),
+            timeout: None,
             resource: resource.into_db_resource(),
             response_type: PhantomData,
         }
@@ -725,6 +1016,7 @@ where
     pub fn update<R>(&self, resource: impl param::Resource<R>) -> Update<C, R> {
         Update {
             router: self.router.extract(),
+            timeout: None,
             resource: resource.into_db_resource(),
             range: None,
             response_type: PhantomData,
@@ -751,6 +1043,7 @@ where
     pub fn delete<R>(&self, resource: impl param::Resource<R>) -> Delete<C, R> {
         Delete {
             router: self.router.extract(),
+            timeout: None,
             resource: resource.into_db_resource(),
             range: None,
             response_type: PhantomData,
@@ -774,6 +1067,7 @@ where
     pub fn version(&self) -> Version<C> {
         Version {
             router: self.router.extract(),
+            timeout: None,
         }
     }
 
@@ -794,22 +1088,156 @@ where
     pub fn health(&self) -> Health<C> {
         Health {
             router: self.router.extract(),
+            timeout: None,
         }
     }
 
-    #[doc(hidden)] // Not supported yet
+    /// Closes the connection to the server
+    ///
+    /// New requests fail with [`ErrorKind::ConnectionClosed`](crate::ErrorKind::ConnectionClosed)
+    /// straight away. Requests in flight get up to 10 seconds to finish, after which the
+    /// connection is closed anyway and this fails with
+    /// [`ErrorKind::Timeout`](crate::ErrorKind::Timeout). WebSocket connections kill their
+    /// live queries and tell the server they are leaving before they close, failing with
+    /// [`ErrorKind::Socket`](crate::ErrorKind::Socket) if they couldn't.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// client.close().deadline(Duration::from_secs(30)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn close(&self) -> Close<C> {
+        Close {
+            router: self.router.extract(),
+            deadline: Duration::from_secs(10),
+        }
+    }
+
+    /// Checks whether the client is currently connected to the server
+    ///
+    /// This is `false` before the client connects, while it is reconnecting
+    /// and once the connection is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// if !client.is_connected() {
+    ///     println!("the database is unreachable");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_connected(&self) -> bool {
+        match self.router.extract() {
+            Ok(router) => router.events.is_connected(),
+            Err(..) => false,
+        }
+    }
+
+    /// Streams changes in the state of the connection
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// use surrealdb_rs::ConnectionEvent;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let mut events = client.connection_events()?;
+    /// while let Some(event) = events.next().await {
+    ///     match event {
+    ///         ConnectionEvent::Disconnected { reason } => println!("disconnected; {reason}"),
+    ///         ConnectionEvent::Reconnected => println!("back online"),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connection_events(&self) -> Result<ConnectionEvents> {
+        let router = self.router.extract()?;
+        Ok(router.events.subscribe())
+    }
+
+    /// Kills a running live query
+    ///
+    /// Live queries are killed automatically when their [`Stream`] is dropped,
+    /// so this is only needed to stop a live query while keeping its stream around.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// # let stream = client.live::<()>("person").await?;
+    /// # let query_id = stream.query_id().clone();
+    /// client.kill(query_id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn kill(&self, query_id: Uuid) -> Kill<C> {
         Kill {
             router: self.router.extract(),
+            timeout: None,
             query_id,
         }
     }
 
-    #[doc(hidden)] // Not supported yet
-    pub fn live(&self, table_name: impl Into<String>) -> Live<C> {
+    /// Listens for changes to the records in a table
+    ///
+    /// Live queries are only supported over WebSockets. The live query is killed
+    /// on the server as soon as the returned stream is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use serde::Deserialize;
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[derive(Debug, Deserialize)]
+    /// # struct Person;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let mut stream = client.live::<Person>("person").await?;
+    /// while let Some(notification) = stream.next().await {
+    ///     let notification = notification?;
+    ///     println!("{:?}: {:?}", notification.action, notification.data);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn live<R>(&self, table_name: impl Into<String>) -> Live<C, R> {
         Live {
-            router: self.router.extract(),
+            router: self.router.extract_owned().and_then(|router| {
+                router.reject_session("live")?;
+                Ok(router)
+            }),
+            timeout: None,
             table_name: table_name.into(),
+            response_type: PhantomData,
         }
     }
 
@@ -837,14 +1265,27 @@ where
     /// # Ok(())
     /// # }
     /// ```
-    #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
-    #[cfg_attr(docsrs, doc(cfg(all(feature = "http", not(target_arch = "wasm32")))))]
+    #[cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            any(feature = "http", feature = "rocksdb"),
+            not(target_arch = "wasm32")
+        )))
+    )]
     pub fn export<P>(&self, file: P) -> Export<C>
     where
         P: AsRef<Path>,
     {
         Export {
-            router: self.router.extract(),
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("export")),
+            timeout: None,
             file: file.as_ref().to_owned(),
         }
     }
@@ -873,14 +1314,27 @@ where
     /// # Ok(())
     /// # }
     /// ```
-    #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
-    #[cfg_attr(docsrs, doc(cfg(all(feature = "http", not(target_arch = "wasm32")))))]
+    #[cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            any(feature = "http", feature = "rocksdb"),
+            not(target_arch = "wasm32")
+        )))
+    )]
     pub fn import<P>(&self, file: P) -> Import<C>
     where
         P: AsRef<Path>,
     {
         Import {
-            router: self.router.extract(),
+            router: self
+                .router
+                .extract()
+                .and_then(|router| router.reject_session("import")),
+            timeout: None,
             file: file.as_ref().to_owned(),
         }
     }
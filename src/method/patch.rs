@@ -11,6 +11,7 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Array;
 use surrealdb::sql::Id;
 use surrealdb::sql::Value;
@@ -19,9 +20,10 @@ use surrealdb::sql::Value;
 #[derive(Debug)]
 pub struct Patch<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) resource: Result<DbResource>,
     pub(super) range: Option<Range<Id>>,
-    pub(super) patches: Vec<Value>,
+    pub(super) patches: Vec<Result<Value>>,
     pub(super) response_type: PhantomData<R>,
 }
 
@@ -51,9 +53,13 @@ where
                 Some(range) => resource.with_range(range)?,
                 None => resource.into(),
             };
-            let patches = Value::Array(Array(self.patches));
-            let mut conn = Client::new(Method::Patch);
-            conn.execute(self.router?, Param::new(vec![param, patches]))
+            let patches = self.patches.into_iter().collect::<Result<Vec<_>>>()?;
+            let patches = Value::Array(Array(patches));
+            self.router?
+                .execute(
+                    Method::Patch,
+                    Param::new(vec![param, patches]).timeout(self.timeout),
+                )
                 .await
         })
     }
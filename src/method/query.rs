@@ -1,17 +1,16 @@
-use crate::method::Method;
 use crate::param;
-use crate::param::from_json;
+use crate::param::to_value;
 use crate::param::Param;
 use crate::Connection;
 use crate::Response;
 use crate::Result;
 use crate::Router;
 use serde::Serialize;
-use serde_json::json;
 use std::collections::BTreeMap;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql;
 use surrealdb::sql::Statement;
 use surrealdb::sql::Statements;
@@ -21,8 +20,9 @@ use surrealdb::sql::Value;
 #[derive(Debug)]
 pub struct Query<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) query: Vec<Result<Vec<Statement>>>,
-    pub(super) bindings: BTreeMap<String, Value>,
+    pub(super) bindings: Result<BTreeMap<String, Value>>,
 }
 
 impl<'r, Client> IntoFuture for Query<'r, Client>
@@ -38,12 +38,14 @@ where
             for query in self.query {
                 statements.extend(query?);
             }
+            let bindings = self.bindings?;
             let mut param = vec![sql::Query(Statements(statements)).to_string().into()];
-            if !self.bindings.is_empty() {
-                param.push(self.bindings.into());
+            if !bindings.is_empty() {
+                param.push(bindings.into());
             }
-            let mut conn = Client::new(Method::Query);
-            conn.execute_query(self.router?, Param::new(param)).await
+            self.router?
+                .execute_query(Param::new(param).timeout(self.timeout))
+                .await
         })
     }
 }
@@ -63,7 +65,10 @@ where
     where
         D: Serialize,
     {
-        self.bindings.insert(key.into(), from_json(json!(value)));
+        self.bindings = self.bindings.and_then(|mut bindings| {
+            bindings.insert(key.into(), to_value(value)?);
+            Ok(bindings)
+        });
         self
     }
 }
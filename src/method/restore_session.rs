@@ -0,0 +1,98 @@
+use crate::method::Method;
+use crate::param::Param;
+use crate::param::SavedSession;
+use crate::param::SessionStore;
+use crate::session::is_auth_failure;
+use crate::Connection;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ErrorKind;
+use crate::Result;
+use crate::Router;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A future that restores a saved session
+#[derive(Debug)]
+pub struct RestoreSession<'r, C: Connection> {
+    pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
+    pub(super) store: Arc<dyn SessionStore>,
+}
+
+impl<'r, Client> IntoFuture for RestoreSession<'r, Client>
+where
+    Client: Connection,
+{
+    type Output = Result<bool>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let router = self.router?;
+            let mut authenticated = false;
+            if let Some(saved) = load(self.store.clone()).await? {
+                if let Some(token) = saved.token.filter(|token| !token.is_expired()) {
+                    let mut conn = Client::new(Method::Authenticate);
+                    match conn
+                        .execute::<()>(
+                            router,
+                            Param::new(vec![token.clone().into()]).timeout(self.timeout),
+                        )
+                        .await
+                    {
+                        Ok(()) => {
+                            router.session.authenticated(Some(&token)).await;
+                            authenticated = true;
+                        }
+                        // the token may have been revoked in the meantime
+                        Err(error) if is_auth_failure(&error) => {
+                            tracing::trace!("Failed to restore the saved token; {error}");
+                        }
+                        Err(error) => return Err(error),
+                    }
+                }
+                // `use` always selects both
+                if let (Some(ns), Some(db)) = (saved.namespace, saved.database) {
+                    let mut conn = Client::new(Method::Use);
+                    conn.execute::<()>(
+                        router,
+                        Param::new(vec![ns.clone().into(), db.clone().into()])
+                            .timeout(self.timeout),
+                    )
+                    .await?;
+                    router.session.used(Some(ns), Some(db)).await;
+                }
+                for (key, value) in saved.vars {
+                    let mut conn = Client::new(Method::Set);
+                    conn.execute::<()>(
+                        router,
+                        Param::new(vec![key.clone().into(), value.clone()]).timeout(self.timeout),
+                    )
+                    .await?;
+                    router.session.var_set(key, value).await;
+                }
+            }
+            // only start saving once the session is restored, so that a failed restore
+            // doesn't overwrite the saved session
+            router.session.set_store(self.store).await;
+            Ok(authenticated)
+        })
+    }
+}
+
+/// Loads the saved session on a thread that is allowed to block, since stores may read files
+#[cfg(not(target_arch = "wasm32"))]
+async fn load(store: Arc<dyn SessionStore>) -> Result<Option<SavedSession>> {
+    match tokio::task::spawn_blocking(move || store.load()).await {
+        Ok(saved) => saved,
+        Err(error) => Err(ErrorKind::InternalError.with_context(error)),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn load(store: Arc<dyn SessionStore>) -> Result<Option<SavedSession>> {
+    store.load()
+}
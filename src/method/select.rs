@@ -10,12 +10,14 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Id;
 
 /// A select future
 #[derive(Debug)]
 pub struct Select<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) resource: Result<DbResource>,
     pub(super) range: Option<Range<Id>>,
     pub(super) response_type: PhantomData<R>,
@@ -34,8 +36,12 @@ where
             Some(range) => resource.with_range(range)?,
             None => resource.into(),
         };
-        let mut conn = Client::new(Method::Select);
-        conn.execute(self.router?, Param::new(vec![param])).await
+        self.router?
+            .execute(
+                Method::Select,
+                Param::new(vec![param]).timeout(self.timeout),
+            )
+            .await
     }
 }
 
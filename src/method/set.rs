@@ -6,12 +6,14 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Value;
 
 /// A set future
 #[derive(Debug)]
 pub struct Set<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) key: String,
     pub(super) value: Result<Value>,
 }
@@ -25,9 +27,19 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            let mut conn = Client::new(Method::Set);
-            conn.execute(self.router?, Param::new(vec![self.key.into(), self.value?]))
-                .await
+            let router = self.router?;
+            let value = self.value?;
+            // sessions bind their parameters to each request instead
+            if router.owner.is_none() {
+                let mut conn = Client::new(Method::Set);
+                conn.execute::<()>(
+                    router,
+                    Param::new(vec![self.key.clone().into(), value.clone()]).timeout(self.timeout),
+                )
+                .await?;
+            }
+            router.session.var_set(self.key, value).await;
+            Ok(())
         })
     }
 }
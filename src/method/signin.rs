@@ -1,19 +1,24 @@
 use crate::method::Method;
+use crate::param::Jwt;
 use crate::param::Param;
 use crate::Connection;
+use crate::ErrorKind;
 use crate::Result;
 use crate::Router;
 use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Value;
 
 /// A signin future
 #[derive(Debug)]
 pub struct Signin<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) credentials: Result<Value>,
     pub(super) response_type: PhantomData<R>,
 }
@@ -28,9 +33,20 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
+            let router = self.router?;
+            let credentials = self.credentials?;
             let mut conn = Client::new(Method::Signin);
-            conn.execute(self.router?, Param::new(vec![self.credentials?]))
-                .await
+            let value: JsonValue = conn
+                .execute(
+                    router,
+                    Param::new(vec![credentials.clone()]).timeout(self.timeout),
+                )
+                .await?;
+            // remember the credentials so the client can sign in again once the token expires
+            let token = serde_json::from_value::<Jwt>(value.clone()).ok();
+            router.session.signed_in(credentials, token.as_ref()).await;
+            serde_json::from_value(value)
+                .map_err(|error| ErrorKind::Deserialization.with_context(error))
         })
     }
 }
@@ -1,19 +1,24 @@
 use crate::method::Method;
+use crate::param::Jwt;
 use crate::param::Param;
 use crate::Connection;
+use crate::ErrorKind;
 use crate::Result;
 use crate::Router;
 use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Value;
 
 /// A signup future
 #[derive(Debug)]
 pub struct Signup<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) credentials: Result<Value>,
     pub(super) response_type: PhantomData<R>,
 }
@@ -28,9 +33,18 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
+            let router = self.router?;
             let mut conn = Client::new(Method::Signup);
-            conn.execute(self.router?, Param::new(vec![self.credentials?]))
-                .await
+            let value: JsonValue = conn
+                .execute(
+                    router,
+                    Param::new(vec![self.credentials?]).timeout(self.timeout),
+                )
+                .await?;
+            let token = serde_json::from_value::<Jwt>(value.clone()).ok();
+            router.session.authenticated(token.as_ref()).await;
+            serde_json::from_value(value)
+                .map_err(|error| ErrorKind::Deserialization.with_context(error))
         })
     }
 }
@@ -1,18 +1,21 @@
-#![cfg(any(feature = "http", feature = "ws"))]
+#![cfg(any(feature = "http", feature = "ws", feature = "mem", feature = "rocksdb"))]
 #![cfg(not(target_arch = "wasm32"))]
 
 mod protocol;
 mod server;
 mod types;
+mod ws;
 
+use crate::method::Stream;
 use crate::param::Database;
 use crate::param::Jwt;
 use crate::param::NameSpace;
 use crate::param::PatchOp;
 use crate::param::Root;
 use crate::param::Scope;
-use crate::Result;
+use crate::Response;
 use crate::StaticClient;
+use crate::Stats;
 use crate::Surreal;
 use protocol::Client;
 use protocol::Test;
@@ -20,7 +23,6 @@ use semver::Version;
 use std::ops::Bound;
 use surrealdb::sql::statements::BeginStatement;
 use surrealdb::sql::statements::CommitStatement;
-use surrealdb::sql::Value;
 use types::AuthParams;
 use types::User;
 use types::USER;
@@ -91,13 +93,25 @@ async fn api() {
     let _: () = CLIENT.authenticate(Jwt(String::new())).await.unwrap();
 
     // query
-    let _: Vec<Result<Vec<Value>>> = CLIENT.query("SELECT * FROM user").await.unwrap();
-    let _: Vec<Result<Vec<Value>>> = CLIENT
+    let _: Response = CLIENT.query("SELECT * FROM user").await.unwrap();
+    let mut response = CLIENT
+        .query("SELECT * FROM user")
+        .query("SELECT * FROM user:john")
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    let _: Vec<User> = response.take(0).unwrap();
+    let _: Option<User> = response.take(1).unwrap();
+    let _: Vec<String> = response.take((0, "name")).unwrap();
+    let _: Option<String> = response.take((1, "name")).unwrap();
+    let _: &[Stats] = response.stats();
+    let _: Response = CLIENT
         .query("CREATE user:john SET name = $name")
         .bind("name", "John Doe")
         .await
         .unwrap();
-    let _: Vec<Result<Vec<Value>>> = CLIENT
+    let _: Response = CLIENT
         .query(BeginStatement)
         .query("CREATE account:one SET balance = 135605.16")
         .query("CREATE account:two SET balance = 91031.31")
@@ -107,6 +121,32 @@ async fn api() {
         .await
         .unwrap();
 
+    // transaction
+    let mut transaction = CLIENT.transaction().await.unwrap();
+    transaction.create((USER, "john")).content(User::default());
+    transaction.update(USER).merge(User::default());
+    transaction
+        .update((USER, "john"))
+        .patch(PatchOp::remove("/name"))
+        .patch(PatchOp::add("/name", "John Doe"));
+    transaction.select(USER).range("jane".."john");
+    transaction.delete((USER, "john"));
+    transaction
+        .query("UPDATE user SET name = $name")
+        .bind("name", "John Doe");
+    let _: Response = transaction.commit().await.unwrap();
+    let mut transaction = CLIENT.transaction().await.unwrap();
+    transaction.create(USER);
+    let _: () = transaction.cancel().await.unwrap();
+    let _: Response = CLIENT
+        .transaction_with(|mut transaction| async move {
+            transaction.update((USER, "john")).merge(User::default());
+            Ok::<_, crate::Error>(transaction)
+        })
+        .retries(3)
+        .await
+        .unwrap();
+
     // create
     let _: User = CLIENT.create(USER).await.unwrap();
     let _: User = CLIENT.create((USER, "john")).await.unwrap();
@@ -188,6 +228,9 @@ async fn api() {
 
     // version
     let _: Version = CLIENT.version().await.unwrap();
+
+    // live
+    let _: Stream<Client, User> = CLIENT.live(USER).await.unwrap();
 }
 
 fn send_and_sync(_: impl Send + Sync) {}
@@ -1,11 +1,15 @@
 use super::server;
+use crate::events::Events;
 use crate::param::from_value;
 use crate::param::DbResponse;
 use crate::param::Param;
 use crate::param::ServerAddrs;
 use crate::param::ToServerAddrs;
+use crate::session::Session;
 use crate::Connection;
 use crate::Method;
+use crate::Requests;
+use crate::Response;
 use crate::Result;
 use crate::Route;
 use crate::Router;
@@ -19,7 +23,6 @@ use std::pin::Pin;
 #[cfg(feature = "ws")]
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
-use surrealdb::sql::Value;
 use url::Url;
 
 #[derive(Debug)]
@@ -33,6 +36,9 @@ impl ToServerAddrs<Test> for () {
             endpoint: Url::parse("test://localhost:8000")?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -51,7 +57,7 @@ impl Connection for Client {
     }
 
     fn connect(
-        _address: ServerAddrs,
+        address: ServerAddrs,
         capacity: usize,
     ) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
         Box::pin(async move {
@@ -59,8 +65,16 @@ impl Connection for Client {
             let router = Router {
                 conn: PhantomData,
                 sender: route_tx,
+                timeout: address.config.request_timeout,
+                events: Events::connected(),
+                requests: Requests::new(),
+                session: Session::new(),
+                owner: None,
+                shutdown: None,
                 #[cfg(feature = "ws")]
                 last_id: AtomicI64::new(0),
+                #[cfg(feature = "ws")]
+                cancelled: None,
             };
             server::mock(route_rx);
             Ok(Surreal {
@@ -110,11 +124,11 @@ impl Connection for Client {
     fn recv_query(
         &mut self,
         rx: Receiver<Self::Response>,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Result<Vec<Value>>>>> + Send + Sync + '_>> {
+    ) -> Pin<Box<dyn Future<Output = Result<Response>> + Send + Sync + '_>> {
         Box::pin(async move {
             let result = rx.into_recv_async().await.unwrap();
             match result.unwrap() {
-                DbResponse::Query(results) => Ok(results),
+                DbResponse::Query(response) => Ok(response),
                 DbResponse::Other(..) => unreachable!(),
             }
         })
@@ -1,15 +1,15 @@
 use super::types::Credentials;
 use super::types::User;
 use crate::method::Method;
-use crate::param::from_json;
 use crate::param::from_value;
+use crate::param::to_value;
 use crate::param::DbResponse;
 use crate::param::Param;
+use crate::Response;
 use crate::Result;
 use crate::Route;
 use flume::Receiver;
 use futures::StreamExt;
-use serde_json::json;
 use surrealdb::sql::Array;
 use surrealdb::sql::Value;
 
@@ -67,16 +67,16 @@ pub(super) fn mock(route_rx: Receiver<Option<Route<(Method, Param), Result<DbRes
                     _ => unreachable!(),
                 },
                 Method::Query => match &params[..] {
-                    [_] | [_, _] => Ok(DbResponse::Query(Vec::new())),
+                    [_] | [_, _] => Ok(DbResponse::Query(Response::default())),
                     _ => unreachable!(),
                 },
                 Method::Create => match &params[..] {
-                    [_] => Ok(DbResponse::Other(from_json(json!(User::default())))),
+                    [_] => Ok(DbResponse::Other(to_value(User::default()).unwrap())),
                     [_, user] => Ok(DbResponse::Other(user.clone())),
                     _ => unreachable!(),
                 },
                 Method::Select => match &params[..] {
-                    [Value::Thing(..)] => Ok(DbResponse::Other(from_json(json!(User::default())))),
+                    [Value::Thing(..)] => Ok(DbResponse::Other(to_value(User::default()).unwrap())),
                     [Value::Table(..) | Value::Array(..) | Value::Range(..)] => {
                         Ok(DbResponse::Other(Value::Array(Array(Vec::new()))))
                     }
@@ -84,7 +84,7 @@ pub(super) fn mock(route_rx: Receiver<Option<Route<(Method, Param), Result<DbRes
                 },
                 Method::Update | Method::Merge | Method::Patch => match &params[..] {
                     [Value::Thing(..)] | [Value::Thing(..), _] => {
-                        Ok(DbResponse::Other(from_json(json!(User::default()))))
+                        Ok(DbResponse::Other(to_value(User::default()).unwrap()))
                     }
                     [Value::Table(..) | Value::Array(..) | Value::Range(..)]
                     | [Value::Table(..) | Value::Array(..) | Value::Range(..), _] => {
@@ -96,12 +96,12 @@ pub(super) fn mock(route_rx: Receiver<Option<Route<(Method, Param), Result<DbRes
                     [_] => Ok(DbResponse::Other(Value::None)),
                     _ => unreachable!(),
                 },
-                #[cfg(feature = "http")]
+                #[cfg(any(feature = "http", feature = "rocksdb"))]
                 Method::Export => match param.file {
                     Some(..) => Ok(DbResponse::Other(Value::None)),
                     _ => unreachable!(),
                 },
-                #[cfg(feature = "http")]
+                #[cfg(any(feature = "http", feature = "rocksdb"))]
                 Method::Import => match param.file {
                     Some(..) => Ok(DbResponse::Other(Value::None)),
                     _ => unreachable!(),
@@ -0,0 +1,342 @@
+#![cfg(feature = "ws")]
+
+//! Tests for the WebSocket router against a server that speaks just enough of the RPC
+//! protocol, so that they can drop the connection at will
+
+use crate::param::Config;
+use crate::protocol::ws::Client;
+use crate::protocol::Any;
+use crate::protocol::Ws;
+use crate::ErrorKind;
+use crate::Result;
+use crate::Surreal;
+use flume::Receiver;
+use futures::SinkExt;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use surrealdb::sql::serde::beg_internal_serialization;
+use surrealdb::sql::serde::end_internal_serialization;
+use surrealdb::sql::Array;
+use surrealdb::sql::Object;
+use surrealdb::sql::Strand;
+use surrealdb::sql::Value;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+const VERSION: &str = "surrealdb-1.0.0-beta.8+20221030.c12a1cc";
+const FIRST_QUERY_ID: &str = "c6c0e36c-e2cf-42cb-b2d5-75415249b261";
+const SECOND_QUERY_ID: &str = "0b3a3f8c-6c1b-4bde-8b1e-5a3b26a31c5e";
+
+#[derive(Debug, Deserialize)]
+struct Person {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NewPerson {
+    name: &'static str,
+}
+
+/// A connection from the client to the test server
+struct Peer(WebSocketStream<TcpStream>);
+
+/// A request the client expects a response to
+#[derive(Debug)]
+struct Request {
+    id: i64,
+    method: String,
+    params: Vec<Value>,
+}
+
+/// Listens on a random port, handing each connection the client makes to the test
+async fn listen() -> (String, Receiver<Peer>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (sender, receiver) = flume::unbounded();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            if sender.send(Peer(socket)).is_err() {
+                break;
+            }
+        }
+    });
+    (address, receiver)
+}
+
+impl Peer {
+    /// Waits for the next request that expects a response
+    ///
+    /// The version check the client makes in the background is answered straight away.
+    async fn request(&mut self) -> Request {
+        while let Some(message) = self.0.next().await {
+            let bytes = match message.unwrap() {
+                Message::Binary(bytes) => bytes,
+                _ => continue,
+            };
+            let mut request = match serde_pack::from_slice(&bytes).unwrap() {
+                Value::Object(Object(request)) => request,
+                value => panic!("unexpected request {value}"),
+            };
+            // pings and requests replayed after reconnecting have no ID
+            let id = match request.remove("id") {
+                Some(id) => id.as_int(),
+                None => continue,
+            };
+            let method = match request.remove("method") {
+                Some(Value::Strand(Strand(method))) => method,
+                method => panic!("unexpected method {method:?}"),
+            };
+            let params = match request.remove("params") {
+                Some(Value::Array(Array(params))) => params,
+                _ => Vec::new(),
+            };
+            if method == "version" {
+                self.respond(id, VERSION.into()).await;
+                continue;
+            }
+            return Request { id, method, params };
+        }
+        panic!("the client disconnected");
+    }
+
+    async fn respond(&mut self, id: i64, result: Value) {
+        self.send(BTreeMap::from([
+            ("id", Value::from(id)),
+            ("result", result),
+        ]))
+        .await;
+    }
+
+    async fn notify(&mut self, query_id: &str, data: Value) {
+        let notification = BTreeMap::from([
+            ("id".to_owned(), Value::from(query_id)),
+            ("action".to_owned(), Value::from("CREATE")),
+            ("result".to_owned(), data),
+        ]);
+        self.send(BTreeMap::from([("result", Value::from(notification))]))
+            .await;
+    }
+
+    async fn send(&mut self, response: BTreeMap<&str, Value>) {
+        // the server sends values in the form `SurrealDB` uses internally
+        beg_internal_serialization();
+        let bytes = serde_pack::to_vec(&response);
+        end_internal_serialization();
+        self.0.send(Message::Binary(bytes.unwrap())).await.unwrap();
+    }
+}
+
+fn person(name: &str) -> Value {
+    Value::from(BTreeMap::from([("name".to_owned(), Value::from(name))]))
+}
+
+fn config() -> Config {
+    // keeps pings out of the way
+    Config::new().ping_interval(Duration::from_secs(60))
+}
+
+#[tokio::test]
+async fn live_queries_survive_reconnects() {
+    let (address, peers) = listen().await;
+    let client = Surreal::connect::<Ws>(address.as_str())
+        .with_config(config())
+        .await
+        .unwrap();
+    let mut peer = peers.recv_async().await.unwrap();
+
+    let live = tokio::spawn({
+        let client = client.clone();
+        async move { client.live::<Person>("person").await }
+    });
+    let request = peer.request().await;
+    assert_eq!(request.method, "live");
+    peer.respond(request.id, FIRST_QUERY_ID.into()).await;
+    let mut stream = live.await.unwrap().unwrap();
+    peer.notify(FIRST_QUERY_ID, person("Jane")).await;
+    let notification = stream.next().await.unwrap().unwrap();
+    assert_eq!(notification.data.name, "Jane");
+
+    // the server goes away and the client reconnects right away
+    drop(peer);
+    let mut peer = peers.recv_async().await.unwrap();
+    let request = peer.request().await;
+    assert_eq!(request.method, "live");
+    assert!(request.id < 0);
+    assert_eq!(request.params, vec![Value::from("person")]);
+    peer.respond(request.id, SECOND_QUERY_ID.into()).await;
+
+    // the stream learns about the gap, then keeps going under the ID it started with
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotificationsMissed);
+    peer.notify(SECOND_QUERY_ID, person("John")).await;
+    let notification = stream.next().await.unwrap().unwrap();
+    assert_eq!(notification.query_id, *stream.query_id());
+    assert_eq!(notification.data.name, "John");
+}
+
+/// Sends a write and a read, waiting until the server has received both
+async fn in_flight(
+    client: &Surreal<Client>,
+    peer: &mut Peer,
+) -> (
+    JoinHandle<Result<Option<Person>>>,
+    JoinHandle<Result<Vec<Person>>>,
+) {
+    let write = tokio::spawn({
+        let client = client.clone();
+        async move {
+            client
+                .create("person:jane")
+                .content(NewPerson { name: "Jane" })
+                .await
+        }
+    });
+    let read = tokio::spawn({
+        let client = client.clone();
+        async move { client.select("person").await }
+    });
+    let mut methods = vec![peer.request().await.method, peer.request().await.method];
+    methods.sort();
+    assert_eq!(methods, ["create", "select"]);
+    (write, read)
+}
+
+#[tokio::test]
+async fn requests_in_flight_fail_when_the_connection_drops() {
+    let (address, peers) = listen().await;
+    let client = Surreal::connect::<Ws>(address.as_str())
+        .with_config(config())
+        .await
+        .unwrap();
+    let mut peer = peers.recv_async().await.unwrap();
+    let (write, read) = in_flight(&client, &mut peer).await;
+
+    drop(peer);
+    let error = write.await.unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionLost);
+    let error = read.await.unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionLost);
+}
+
+#[tokio::test]
+async fn reads_in_flight_are_resent_after_reconnecting() {
+    let (address, peers) = listen().await;
+    let client = Surreal::connect::<Ws>(address.as_str())
+        .with_config(config().resend_reads(true))
+        .await
+        .unwrap();
+    let mut peer = peers.recv_async().await.unwrap();
+    let (write, read) = in_flight(&client, &mut peer).await;
+
+    // the write may already have been applied, so only the read is sent again
+    drop(peer);
+    let error = write.await.unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionLost);
+    let mut peer = peers.recv_async().await.unwrap();
+    let request = peer.request().await;
+    assert_eq!(request.method, "select");
+    assert_eq!(request.params, vec![Value::from("person")]);
+    peer.respond(request.id, Value::from(vec![person("Jane")]))
+        .await;
+    let people = read.await.unwrap().unwrap();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].name, "Jane");
+}
+
+#[tokio::test]
+async fn closing_kills_live_queries() {
+    let (address, peers) = listen().await;
+    let client = Surreal::connect::<Ws>(address.as_str())
+        .with_config(config())
+        .await
+        .unwrap();
+    let mut peer = peers.recv_async().await.unwrap();
+
+    let live = tokio::spawn({
+        let client = client.clone();
+        async move { client.live::<Person>("person").await }
+    });
+    let request = peer.request().await;
+    peer.respond(request.id, FIRST_QUERY_ID.into()).await;
+    let _stream = live.await.unwrap().unwrap();
+
+    let close = tokio::spawn({
+        let client = client.clone();
+        async move { client.close().await }
+    });
+    let bytes = match peer.0.next().await.unwrap().unwrap() {
+        Message::Binary(bytes) => bytes,
+        message => panic!("unexpected message {message:?}"),
+    };
+    let method = match serde_pack::from_slice(&bytes).unwrap() {
+        Value::Object(Object(mut request)) => request.remove("method"),
+        value => panic!("unexpected request {value}"),
+    };
+    assert_eq!(method, Some(Value::from("kill")));
+    let message = peer.0.next().await.unwrap().unwrap();
+    assert!(matches!(message, Message::Close(..)));
+    close.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn any_forwards_requests_over_websockets() {
+    let (address, peers) = listen().await;
+    let client = Surreal::connect::<Any>(format!("ws://{address}"))
+        .with_config(config())
+        .await
+        .unwrap();
+    let mut peer = peers.recv_async().await.unwrap();
+
+    let read = tokio::spawn({
+        let client = client.clone();
+        async move { client.select::<Vec<Person>>("person").await }
+    });
+    let request = peer.request().await;
+    assert_eq!(request.method, "select");
+    peer.respond(request.id, Value::from(vec![person("Jane")]))
+        .await;
+    let people = read.await.unwrap().unwrap();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].name, "Jane");
+}
+
+#[tokio::test]
+async fn any_cancels_requests_dropped_before_they_are_answered() {
+    let (address, peers) = listen().await;
+    let client = Surreal::connect::<Any>(format!("ws://{address}"))
+        .with_config(config().resend_reads(true))
+        .await
+        .unwrap();
+    let mut peer = peers.recv_async().await.unwrap();
+
+    let read = tokio::spawn({
+        let client = client.clone();
+        async move { client.select::<Vec<Person>>("person").await }
+    });
+    let request = peer.request().await;
+    assert_eq!(request.method, "select");
+    read.abort();
+    assert!(read.await.unwrap_err().is_cancelled());
+    // gives the cancellation time to reach the WebSocket router
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // a read that is still in flight would be sent again on the new connection
+    drop(peer);
+    let mut peer = peers.recv_async().await.unwrap();
+    let read = tokio::spawn({
+        let client = client.clone();
+        async move { client.select::<Vec<Person>>("animal").await }
+    });
+    let request = peer.request().await;
+    assert_eq!(request.params, vec![Value::from("animal")]);
+    peer.respond(request.id, Value::from(Vec::<Value>::new()))
+        .await;
+    assert!(read.await.unwrap().unwrap().is_empty());
+}
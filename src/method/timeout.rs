@@ -0,0 +1,92 @@
+use crate::method::Authenticate;
+use crate::method::Commit;
+use crate::method::Content;
+use crate::method::Create;
+use crate::method::Delete;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use crate::method::Export;
+use crate::method::Health;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use crate::method::Import;
+use crate::method::Invalidate;
+use crate::method::Kill;
+use crate::method::Live;
+use crate::method::Merge;
+use crate::method::Patch;
+use crate::method::Query;
+use crate::method::RestoreSession;
+use crate::method::Select;
+use crate::method::Set;
+use crate::method::Signin;
+use crate::method::Signup;
+use crate::method::Unset;
+use crate::method::Update;
+use crate::method::UseNsDb;
+use crate::method::Version;
+use crate::Connection;
+use crate::WithTimeout;
+use std::time::Duration;
+
+/// Keeps [`WithTimeout`] from being implemented outside of this crate
+pub trait Sealed {}
+
+/// Implements [`WithTimeout`] for request futures, which pass the timeout on to the request
+macro_rules! with_timeout {
+    ($($(#[$meta:meta])* $future:ident<$($param:tt),*>,)*) => {
+        $(
+            $(#[$meta])*
+            impl<$($param),*> Sealed for $future<$($param),*> where C: Connection {}
+
+            $(#[$meta])*
+            impl<$($param),*> WithTimeout for $future<$($param),*>
+            where
+                C: Connection,
+            {
+                fn timeout(mut self, duration: Duration) -> Self {
+                    self.timeout = Some(duration);
+                    self
+                }
+            }
+        )*
+    };
+}
+
+with_timeout! {
+    Authenticate<'r, C>,
+    Commit<C>,
+    Content<'r, C, D, R>,
+    Create<'r, C, R>,
+    Delete<'r, C, R>,
+    #[cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    ))]
+    Export<'r, C>,
+    Health<'r, C>,
+    #[cfg(all(
+        any(feature = "http", feature = "rocksdb"),
+        not(target_arch = "wasm32")
+    ))]
+    Import<'r, C>,
+    Invalidate<'r, C>,
+    Kill<'r, C>,
+    Live<C, R>,
+    Merge<'r, C, D, R>,
+    Patch<'r, C, R>,
+    Query<'r, C>,
+    RestoreSession<'r, C>,
+    Select<'r, C, R>,
+    Set<'r, C>,
+    Signin<'r, C, R>,
+    Signup<'r, C, R>,
+    Unset<'r, C>,
+    Update<'r, C, R>,
+    UseNsDb<'r, C>,
+    Version<'r, C>,
+}
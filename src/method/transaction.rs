@@ -0,0 +1,487 @@
+use crate::method::Cancel;
+use crate::method::Commit;
+use crate::method::Method;
+use crate::param;
+use crate::param::to_value;
+use crate::param::DbResource;
+use crate::param::PatchOp;
+use crate::param::Query as _;
+use crate::param::QueryResult;
+use crate::param::Range;
+use crate::Connection;
+use crate::Response;
+use crate::Result;
+use crate::Surreal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use surrealdb::sql;
+use surrealdb::sql::Array;
+use surrealdb::sql::Id;
+use surrealdb::sql::Statement;
+use surrealdb::sql::Value;
+
+/// An ongoing transaction
+///
+/// Statements issued through a transaction are buffered on the client. They are only sent to
+/// the server, as a single `BEGIN ... COMMIT` block, when the transaction is committed.
+#[derive(Debug)]
+pub struct Transaction<C: Connection> {
+    pub(super) client: Surreal<C>,
+    pub(super) statements: Vec<Buffered>,
+    pub(super) bindings: Result<BTreeMap<String, Value>>,
+    /// The number of results the buffered statements will produce
+    pub(super) results: usize,
+}
+
+impl<C> Transaction<C>
+where
+    C: Connection,
+{
+    /// Adds a raw query to the transaction
+    ///
+    /// Every statement in the query produces a result, so records added after a query with
+    /// several statements are shifted accordingly. Handles returned by the other methods take
+    /// this into account.
+    pub fn query(&mut self, query: impl param::Query) -> &mut Self {
+        let query = query.try_into_query();
+        if let Ok(statements) = &query {
+            self.results += statements
+                .iter()
+                .filter(|statement| produces_result(statement))
+                .count();
+        }
+        self.statements.push(Buffered::Query(query));
+        self
+    }
+
+    /// Binds a parameter to the queries in the transaction
+    pub fn bind<D>(&mut self, key: impl Into<String>, value: D) -> &mut Self
+    where
+        D: Serialize,
+    {
+        let value = to_value(value);
+        if let Ok(bindings) = &mut self.bindings {
+            match value {
+                Ok(value) => {
+                    bindings.insert(key.into(), value);
+                }
+                Err(error) => self.bindings = Err(error),
+            }
+        }
+        self
+    }
+
+    /// Selects a record or records from a table as part of the transaction
+    pub fn select<R>(&mut self, resource: impl param::Resource<R>) -> TransactionSelect<C, R>
+    where
+        R: RawResponse,
+    {
+        let (index, result) = self.push(Method::Select, resource.into_db_resource());
+        TransactionSelect {
+            transaction: self,
+            index,
+            result,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Creates a record in the database as part of the transaction
+    pub fn create<R>(&mut self, resource: impl param::Resource<R>) -> TransactionCreate<C, R>
+    where
+        R: RawResponse,
+    {
+        let (index, result) = self.push(Method::Create, resource.into_db_resource());
+        TransactionCreate {
+            transaction: self,
+            index,
+            result,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Updates all records in a table, or a specific record, as part of the transaction
+    pub fn update<R>(&mut self, resource: impl param::Resource<R>) -> TransactionUpdate<C, R>
+    where
+        R: RawResponse,
+    {
+        let (index, result) = self.push(Method::Update, resource.into_db_resource());
+        TransactionUpdate {
+            transaction: self,
+            index,
+            result,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Deletes all records, or a specific record, as part of the transaction
+    pub fn delete<R>(&mut self, resource: impl param::Resource<R>) -> TransactionDelete<C, R>
+    where
+        R: RawResponse,
+    {
+        let (index, result) = self.push(Method::Delete, resource.into_db_resource());
+        TransactionDelete {
+            transaction: self,
+            index,
+            result,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Creates a commit future
+    ///
+    /// Sends all the buffered statements to the server and resolves to one result per statement.
+    /// The results of records added to the transaction can be taken using their [`Handle`]s.
+    pub fn commit(self) -> Commit<C> {
+        Commit {
+            transaction: self,
+            timeout: None,
+        }
+    }
+
+    /// Creates a cancel future
+    ///
+    /// Discards all the buffered statements without sending them to the server.
+    pub fn cancel(self) -> Cancel<C> {
+        Cancel { transaction: self }
+    }
+
+    /// Buffers a record statement, returning its position in the transaction and the index of
+    /// its result in the response
+    fn push(&mut self, method: Method, resource: Result<DbResource>) -> (usize, usize) {
+        self.statements.push(Buffered::Record(Record {
+            method,
+            resource,
+            range: None,
+            data: None,
+        }));
+        let result = self.results;
+        self.results += 1;
+        (self.statements.len() - 1, result)
+    }
+
+    fn record(&mut self, index: usize) -> &mut Record {
+        match &mut self.statements[index] {
+            Buffered::Record(record) => record,
+            Buffered::Query(..) => unreachable!(),
+        }
+    }
+}
+
+/// The raw responses a statement in a transaction can resolve to
+///
+/// This allows the type of a resource to be inferred without naming a response type.
+pub trait RawResponse {}
+
+impl RawResponse for Option<Value> {}
+
+impl RawResponse for Vec<Value> {}
+
+/// Whether the server returns a result for a statement
+///
+/// The statements that control transactions are executed without producing one.
+fn produces_result(statement: &Statement) -> bool {
+    !matches!(
+        statement,
+        Statement::Begin(..) | Statement::Cancel(..) | Statement::Commit(..)
+    )
+}
+
+/// A handle to the result of a record statement in a transaction
+///
+/// Passing it to [`Response::take`](crate::Response::take) on the response of the commit
+/// deserializes the result of that statement.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use serde::Deserialize;
+/// # use surrealdb_rs::{Result, Surreal};
+/// # use surrealdb_rs::net::WsClient;
+/// # #[derive(Deserialize)]
+/// # struct Account {
+/// #     balance: f64,
+/// # }
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// # let client = Surreal::<WsClient>::new();
+/// let mut transaction = client.transaction().await?;
+/// transaction.query("UPDATE account SET balance += 10; UPDATE account SET balance -= 5");
+/// let one = transaction.select(("account", "one")).handle();
+/// let all = transaction.select("account").handle();
+/// let mut response = transaction.commit().await?;
+/// let one: Option<Account> = response.take(one)?;
+/// let all: Vec<Account> = response.take(all)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Handle<R> {
+    index: usize,
+    response_type: PhantomData<R>,
+}
+
+impl<R> Handle<R> {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            response_type: PhantomData,
+        }
+    }
+
+    /// The index of the result in the response to the commit
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<R> Clone for Handle<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for Handle<R> {}
+
+impl<T> QueryResult<Option<T>> for Handle<Option<Value>>
+where
+    T: DeserializeOwned,
+{
+    fn query_result(self, response: &mut Response) -> Result<Option<T>> {
+        self.index.query_result(response)
+    }
+}
+
+impl<T> QueryResult<Vec<T>> for Handle<Vec<Value>>
+where
+    T: DeserializeOwned,
+{
+    fn query_result(self, response: &mut Response) -> Result<Vec<T>> {
+        self.index.query_result(response)
+    }
+}
+
+/// A statement buffered in a transaction
+#[derive(Debug)]
+pub(super) enum Buffered {
+    Query(Result<Vec<Statement>>),
+    Record(Record),
+}
+
+/// A statement operating on records, rendered the same way the server renders RPC methods
+#[derive(Debug)]
+pub(super) struct Record {
+    method: Method,
+    resource: Result<DbResource>,
+    range: Option<Range<Id>>,
+    data: Option<Result<Value>>,
+}
+
+impl Buffered {
+    /// Converts the buffered statement into SQL statements
+    ///
+    /// The record and data a statement operates on are passed as bindings named after the
+    /// position of the statement in the transaction.
+    pub(super) fn into_statements(
+        self,
+        index: usize,
+        bindings: &mut BTreeMap<String, Value>,
+    ) -> Result<Vec<Statement>> {
+        let Record {
+            method,
+            resource,
+            range,
+            data,
+        } = match self {
+            Buffered::Query(query) => return query,
+            Buffered::Record(record) => record,
+        };
+        let resource = resource?;
+        let what = match range {
+            Some(range) => resource.with_range(range)?,
+            None => resource.into(),
+        };
+        let what_key = format!("_transaction_what_{index}");
+        let data_key = format!("_transaction_data_{index}");
+        let query = match (method, &data) {
+            (Method::Select, _) => format!("SELECT * FROM ${what_key}"),
+            (Method::Create, None) => format!("CREATE ${what_key}"),
+            (Method::Create, Some(..)) => format!("CREATE ${what_key} CONTENT ${data_key}"),
+            (Method::Update, None) => format!("UPDATE ${what_key}"),
+            (Method::Update, Some(..)) => format!("UPDATE ${what_key} CONTENT ${data_key}"),
+            (Method::Merge, _) => format!("UPDATE ${what_key} MERGE ${data_key}"),
+            (Method::Patch, _) => format!("UPDATE ${what_key} PATCH ${data_key}"),
+            (Method::Delete, _) => format!("DELETE ${what_key}"),
+            _ => unreachable!(),
+        };
+        bindings.insert(what_key, what);
+        if let Some(data) = data {
+            bindings.insert(data_key, data?);
+        }
+        sql::parse(&query)?.try_into_query()
+    }
+}
+
+/// A select statement in a transaction
+#[derive(Debug)]
+pub struct TransactionSelect<'t, C: Connection, R> {
+    transaction: &'t mut Transaction<C>,
+    index: usize,
+    result: usize,
+    response_type: PhantomData<R>,
+}
+
+impl<C, R> TransactionSelect<'_, C, R>
+where
+    C: Connection,
+{
+    /// Returns a handle to the result of the statement
+    pub fn handle(self) -> Handle<R> {
+        Handle::new(self.result)
+    }
+}
+
+impl<C> TransactionSelect<'_, C, Vec<Value>>
+where
+    C: Connection,
+{
+    /// Restricts the records selected to those in the specified range
+    pub fn range(self, bounds: impl Into<Range<Id>>) -> Self {
+        self.transaction.record(self.index).range = Some(bounds.into());
+        self
+    }
+}
+
+/// A create statement in a transaction
+#[derive(Debug)]
+pub struct TransactionCreate<'t, C: Connection, R> {
+    transaction: &'t mut Transaction<C>,
+    index: usize,
+    result: usize,
+    response_type: PhantomData<R>,
+}
+
+impl<C, R> TransactionCreate<'_, C, R>
+where
+    C: Connection,
+{
+    /// Returns a handle to the result of the statement
+    pub fn handle(self) -> Handle<R> {
+        Handle::new(self.result)
+    }
+}
+
+impl<C, R> TransactionCreate<'_, C, R>
+where
+    C: Connection,
+{
+    /// Sets content of a record
+    pub fn content<D>(self, data: D) -> Handle<R>
+    where
+        D: Serialize,
+    {
+        self.transaction.record(self.index).data = Some(to_value(data));
+        self.handle()
+    }
+}
+
+/// An update statement in a transaction
+#[derive(Debug)]
+pub struct TransactionUpdate<'t, C: Connection, R> {
+    transaction: &'t mut Transaction<C>,
+    index: usize,
+    result: usize,
+    response_type: PhantomData<R>,
+}
+
+impl<C, R> TransactionUpdate<'_, C, R>
+where
+    C: Connection,
+{
+    /// Returns a handle to the result of the statement
+    pub fn handle(self) -> Handle<R> {
+        Handle::new(self.result)
+    }
+}
+
+impl<C> TransactionUpdate<'_, C, Vec<Value>>
+where
+    C: Connection,
+{
+    /// Restricts the records to update to those in the specified range
+    pub fn range(self, bounds: impl Into<Range<Id>>) -> Self {
+        self.transaction.record(self.index).range = Some(bounds.into());
+        self
+    }
+}
+
+impl<C, R> TransactionUpdate<'_, C, R>
+where
+    C: Connection,
+{
+    /// Replaces the current document / record data with the specified data
+    pub fn content<D>(self, data: D) -> Handle<R>
+    where
+        D: Serialize,
+    {
+        self.transaction.record(self.index).data = Some(to_value(data));
+        self.handle()
+    }
+
+    /// Merges the current document / record data with the specified data
+    pub fn merge<D>(self, data: D) -> Handle<R>
+    where
+        D: Serialize,
+    {
+        let record = self.transaction.record(self.index);
+        record.method = Method::Merge;
+        record.data = Some(to_value(data));
+        self.handle()
+    }
+
+    /// Patches the current document / record data with the specified JSON Patch data
+    ///
+    /// Can be called repeatedly to apply several changes.
+    pub fn patch(self, PatchOp(patch): PatchOp) -> Self {
+        let record = self.transaction.record(self.index);
+        record.method = Method::Patch;
+        match (&mut record.data, patch) {
+            // the first error is the one reported on commit
+            (Some(Err(..)), _) => {}
+            (Some(Ok(Value::Array(Array(patches)))), Ok(patch)) => patches.push(patch),
+            (data, patch) => *data = Some(patch.map(|patch| Value::Array(Array(vec![patch])))),
+        }
+        self
+    }
+}
+
+/// A delete statement in a transaction
+#[derive(Debug)]
+pub struct TransactionDelete<'t, C: Connection, R> {
+    transaction: &'t mut Transaction<C>,
+    index: usize,
+    result: usize,
+    response_type: PhantomData<R>,
+}
+
+impl<C, R> TransactionDelete<'_, C, R>
+where
+    C: Connection,
+{
+    /// Returns a handle to the result of the statement
+    pub fn handle(self) -> Handle<R> {
+        Handle::new(self.result)
+    }
+}
+
+impl<C> TransactionDelete<'_, C, Vec<Value>>
+where
+    C: Connection,
+{
+    /// Restricts the records to delete to those in the specified range
+    pub fn range(self, bounds: impl Into<Range<Id>>) -> Self {
+        self.transaction.record(self.index).range = Some(bounds.into());
+        self
+    }
+}
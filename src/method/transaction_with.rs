@@ -0,0 +1,90 @@
+use crate::method::Transaction;
+use crate::param::Reconnect;
+use crate::Connection;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Response;
+use crate::Surreal;
+use std::fmt;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::time::Duration;
+
+pub(super) const DEFAULT_RETRIES: u32 = 5;
+/// How long to wait before retrying, with the same jitter used when reconnecting so that
+/// transactions which conflicted with each other don't keep colliding
+const BACKOFF: Reconnect = Reconnect::backoff(Duration::from_millis(50), Duration::from_secs(2));
+
+/// A future that runs a transaction, retrying it on conflict
+pub struct TransactionWith<C: Connection, F> {
+    pub(super) client: Surreal<C>,
+    pub(super) function: F,
+    pub(super) retries: u32,
+}
+
+impl<C, F> TransactionWith<C, F>
+where
+    C: Connection,
+{
+    /// Sets the maximum number of times to retry the transaction when it conflicts with
+    /// another one
+    ///
+    /// Defaults to 5. Setting it to 0 disables retries.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+}
+
+impl<C, F> fmt::Debug for TransactionWith<C, F>
+where
+    C: Connection,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionWith")
+            .field("client", &self.client)
+            .field("retries", &self.retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Client, F, Fut, E> IntoFuture for TransactionWith<Client, F>
+where
+    Client: Connection,
+    F: FnMut(Transaction<Client>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Transaction<Client>, E>> + Send + Sync + 'static,
+    E: From<Error> + Send + Sync + 'static,
+{
+    type Output = Result<Response, E>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                let transaction = self.client.transaction().await?;
+                // returning an error drops the transaction, cancelling it
+                let transaction = (self.function)(transaction).await?;
+                let response = transaction.commit().await?;
+                let conflict = response.results.iter().find_map(|result| match result {
+                    Err(error) if error.kind() == ErrorKind::TransactionConflict => {
+                        Some(error.clone())
+                    }
+                    _ => None,
+                });
+                match conflict {
+                    None => return Ok(response),
+                    Some(error) if attempt >= self.retries => return Err(error.into()),
+                    Some(error) => {
+                        // the first attempt of a backoff is immediate, so retries start at 1
+                        attempt += 1;
+                        let backoff = BACKOFF.delay(attempt).unwrap_or_default();
+                        tracing::trace!("Retrying transaction in {backoff:?}; {error}");
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+            }
+        })
+    }
+}
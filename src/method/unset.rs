@@ -6,11 +6,13 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// An unset future
 #[derive(Debug)]
 pub struct Unset<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) key: String,
 }
 
@@ -23,9 +25,18 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            let mut conn = Client::new(Method::Unset);
-            conn.execute(self.router?, Param::new(vec![self.key.into()]))
-                .await
+            let router = self.router?;
+            // sessions bind their parameters to each request instead
+            if router.owner.is_none() {
+                let mut conn = Client::new(Method::Unset);
+                conn.execute::<()>(
+                    router,
+                    Param::new(vec![self.key.clone().into()]).timeout(self.timeout),
+                )
+                .await?;
+            }
+            router.session.var_unset(&self.key).await;
+            Ok(())
         })
     }
 }
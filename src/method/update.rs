@@ -15,12 +15,14 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 use surrealdb::sql::Id;
 
 /// An update future
 #[derive(Debug)]
 pub struct Update<'r, C: Connection, R> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) resource: Result<DbResource>,
     pub(super) range: Option<Range<Id>>,
     pub(super) response_type: PhantomData<R>,
@@ -39,8 +41,12 @@ where
             Some(range) => resource.with_range(range)?,
             None => resource.into(),
         };
-        let mut conn = Client::new(Method::Update);
-        conn.execute(self.router?, Param::new(vec![param])).await
+        self.router?
+            .execute(
+                Method::Update,
+                Param::new(vec![param]).timeout(self.timeout),
+            )
+            .await
     }
 }
 
@@ -95,6 +101,7 @@ macro_rules! update_methods {
             {
                 Content {
                     router: self.router,
+                    timeout: self.timeout,
                     method: Method::Update,
                     resource: self.resource,
                     range: self.range,
@@ -110,6 +117,7 @@ macro_rules! update_methods {
             {
                 Merge {
                     router: self.router,
+                    timeout: self.timeout,
                     resource: self.resource,
                     range: self.range,
                     content: data,
@@ -121,6 +129,7 @@ macro_rules! update_methods {
             pub fn patch(self, PatchOp(patch): PatchOp) -> Patch<'r, C, $res> {
                 Patch {
                     router: self.router,
+                    timeout: self.timeout,
                     resource: self.resource,
                     range: self.range,
                     patches: vec![patch],
@@ -1,11 +1,13 @@
 use crate::method::Method;
 use crate::param::Param;
+use crate::scope::ident;
 use crate::Connection;
 use crate::Result;
 use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// Stores the namespace to use
 #[derive(Debug)]
@@ -18,6 +20,7 @@ pub struct UseNs<'r, C: Connection> {
 #[derive(Debug)]
 pub struct UseNsDb<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
     pub(super) ns: String,
     pub(super) db: String,
 }
@@ -32,6 +35,7 @@ where
             db: db.into(),
             ns: self.ns,
             router: self.router,
+            timeout: None,
         }
     }
 }
@@ -45,12 +49,25 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            let mut conn = Client::new(Method::Use);
-            conn.execute(
-                self.router?,
-                Param::new(vec![self.ns.into(), self.db.into()]),
-            )
-            .await
+            let router = self.router?;
+            match router.owner {
+                None => {
+                    let mut conn = Client::new(Method::Use);
+                    conn.execute::<()>(
+                        router,
+                        Param::new(vec![self.ns.clone().into(), self.db.clone().into()])
+                            .timeout(self.timeout),
+                    )
+                    .await?;
+                }
+                // sessions select them at the start of each request instead
+                Some(..) => {
+                    ident(&self.ns)?;
+                    ident(&self.db)?;
+                }
+            }
+            router.session.used(Some(self.ns), Some(self.db)).await;
+            Ok(())
         })
     }
 }
@@ -6,11 +6,13 @@ use crate::Router;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A version future
 #[derive(Debug)]
 pub struct Version<'r, C: Connection> {
     pub(super) router: Result<&'r Router<C>>,
+    pub(super) timeout: Option<Duration>,
 }
 
 impl<'r, Client> IntoFuture for Version<'r, Client>
@@ -23,7 +25,9 @@ where
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async {
             let mut conn = Client::new(Method::Version);
-            let version: String = conn.execute(self.router?, Param::new(Vec::new())).await?;
+            let version: String = conn
+                .execute(self.router?, Param::new(Vec::new()).timeout(self.timeout))
+                .await?;
             let semantic = version.trim_start_matches("surrealdb-");
             semantic.parse().map_err(Into::into)
         })
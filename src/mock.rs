@@ -0,0 +1,319 @@
+//! A programmable mock connection for testing code that uses the client
+//!
+//! Register the requests your code is expected to make on a [`MockServer`], along with the
+//! responses they should get back, then connect to it using the [`Mock`] scheme.
+//!
+//! # Examples
+//!
+//! ```
+//! use surrealdb::sql::Value;
+//! use surrealdb_rs::method::Method;
+//! use surrealdb_rs::mock::Mock;
+//! use surrealdb_rs::mock::MockServer;
+//! use surrealdb_rs::param::DbResponse;
+//! use surrealdb_rs::ErrorKind;
+//! use surrealdb_rs::Surreal;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb_rs::Result<()> {
+//! let server = MockServer::new();
+//! server
+//!     .expect(Method::Use)
+//!     .with_params(vec!["namespace".into(), "database".into()])
+//!     .returns(DbResponse::Other(Value::None));
+//! server
+//!     .expect(Method::Select)
+//!     .fails(ErrorKind::Query.with_message("permission denied"));
+//!
+//! let client = Surreal::connect::<Mock>(&server).await?;
+//! client.use_ns("namespace").use_db("database").await?;
+//! let result: surrealdb_rs::Result<Vec<serde_json::Value>> = client.select("user").await;
+//! assert_eq!(result.unwrap_err().kind(), ErrorKind::Query);
+//!
+//! // Panics if any expectation was not met or an unexpected request was made
+//! server.verify();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::events::Events;
+use crate::param::from_value;
+use crate::param::DbResponse;
+use crate::param::Harness;
+use crate::param::Param;
+use crate::param::ServerAddrs;
+use crate::session::Session;
+use crate::Connection;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Method;
+use crate::Requests;
+use crate::Response as QueryResponse;
+use crate::Result;
+use crate::Route;
+use crate::Router;
+use crate::Surreal;
+use flume::Receiver;
+use futures::StreamExt;
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+#[cfg(feature = "ws")]
+use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
+use std::sync::Mutex;
+use surrealdb::sql::Value;
+
+type MockRoute = Route<(Method, Param), Result<DbResponse>>;
+
+/// The scheme used to connect to a [`MockServer`]
+#[derive(Debug)]
+pub struct Mock;
+
+/// A client for communicating with a [`MockServer`]
+#[derive(Debug, Clone)]
+pub struct Client {
+    method: Method,
+}
+
+#[derive(Debug)]
+struct Expected {
+    method: Method,
+    params: Option<Vec<Value>>,
+    response: Result<DbResponse>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    expected: Vec<Expected>,
+    unexpected: Vec<String>,
+}
+
+/// A mock server that answers requests with canned responses
+///
+/// Cloning the server yields a handle to the same set of expectations.
+#[derive(Debug, Clone, Default)]
+pub struct MockServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    /// Creates a mock server with no expectations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects a request for the given method
+    ///
+    /// Each expectation answers a single request. Requests are matched against expectations
+    /// in the order they were registered.
+    pub fn expect(&self, method: Method) -> Expectation<'_> {
+        Expectation {
+            server: self,
+            method,
+            params: None,
+        }
+    }
+
+    /// Asserts that all expectations were met and no unexpected requests were made
+    ///
+    /// # Panics
+    ///
+    /// Panics if any expected request was never made or a request did not match any
+    /// expectation.
+    pub fn verify(&self) {
+        let state = self.state.lock().unwrap();
+        let mut failures: Vec<_> = state
+            .expected
+            .iter()
+            .map(|expected| {
+                format!(
+                    "expected a `{}` request that was never made",
+                    expected.method.as_str()
+                )
+            })
+            .collect();
+        failures.extend(state.unexpected.iter().cloned());
+        assert!(
+            failures.is_empty(),
+            "mock expectations were not met;\n{}",
+            failures.join("\n")
+        );
+    }
+
+    fn respond(&self, method: Method, params: Vec<Value>) -> Result<DbResponse> {
+        let mut state = self.state.lock().unwrap();
+        let position = state.expected.iter().position(|expected| {
+            expected.method == method
+                && expected
+                    .params
+                    .as_ref()
+                    .map_or(true, |expected| *expected == params)
+        });
+        match position {
+            Some(index) => state.expected.remove(index).response,
+            None => {
+                let message = format!(
+                    "unexpected `{}` request with params {params:?}",
+                    method.as_str()
+                );
+                state.unexpected.push(message.clone());
+                Err(ErrorKind::InvalidRequest.with_message(message))
+            }
+        }
+    }
+}
+
+/// A request expected by a [`MockServer`]
+///
+/// The expectation is only registered once a response is set on it.
+#[derive(Debug)]
+#[must_use = "expectations are only registered once a response is set on them"]
+pub struct Expectation<'a> {
+    server: &'a MockServer,
+    method: Method,
+    params: Option<Vec<Value>>,
+}
+
+impl Expectation<'_> {
+    /// Only matches requests with exactly these parameters
+    ///
+    /// By default, requests match regardless of their parameters.
+    pub fn with_params(mut self, params: Vec<Value>) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Answers the request with a response
+    pub fn returns(self, response: DbResponse) {
+        self.respond(Ok(response));
+    }
+
+    /// Answers the request with an error
+    pub fn fails(self, error: Error) {
+        self.respond(Err(error));
+    }
+
+    fn respond(self, response: Result<DbResponse>) {
+        let mut state = self.server.state.lock().unwrap();
+        state.expected.push(Expected {
+            method: self.method,
+            params: self.params,
+            response,
+        });
+    }
+}
+
+impl Connection for Client {
+    type Request = (Method, Param);
+    type Response = Result<DbResponse>;
+
+    fn new(method: Method) -> Self {
+        Self { method }
+    }
+
+    fn connect(
+        address: ServerAddrs,
+        capacity: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
+        Box::pin(async move {
+            let server = match address.harness {
+                Some(Harness::Mock(server)) => server,
+                _ => MockServer::default(),
+            };
+
+            let (route_tx, route_rx) = match capacity {
+                0 => flume::unbounded(),
+                capacity => flume::bounded(capacity),
+            };
+
+            router(server, route_rx);
+
+            Ok(Surreal {
+                router: OnceCell::with_value(Arc::new(Router {
+                    conn: PhantomData,
+                    sender: route_tx,
+                    timeout: address.config.request_timeout,
+                    events: Events::connected(),
+                    requests: Requests::new(),
+                    session: Session::new(),
+                    owner: None,
+                    shutdown: None,
+                    #[cfg(feature = "ws")]
+                    last_id: AtomicI64::new(0),
+                    #[cfg(feature = "ws")]
+                    cancelled: None,
+                })),
+            })
+        })
+    }
+
+    fn send<'r>(
+        &'r mut self,
+        router: &'r Router<Self>,
+        param: Param,
+    ) -> Pin<Box<dyn Future<Output = Result<Receiver<Self::Response>>> + Send + Sync + 'r>> {
+        Box::pin(async move {
+            let (sender, receiver) = flume::bounded(1);
+            let route = Route {
+                request: (self.method, param),
+                response: sender,
+            };
+            router.sender.send_async(Some(route)).await?;
+            Ok(receiver)
+        })
+    }
+
+    fn recv<R>(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<R>> + Send + Sync + '_>>
+    where
+        R: DeserializeOwned,
+    {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Other(value) => from_value(&value),
+                DbResponse::Query(..) => Err(ErrorKind::InvalidRequest
+                    .with_message("the mock returned a query response for a non-query request")),
+            }
+        })
+    }
+
+    fn recv_query(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<QueryResponse>> + Send + Sync + '_>> {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Query(response) => Ok(response),
+                DbResponse::Other(..) => Err(ErrorKind::InvalidRequest
+                    .with_message("the mock returned a non-query response for a query request")),
+            }
+        })
+    }
+}
+
+fn router(server: MockServer, route_rx: Receiver<Option<MockRoute>>) {
+    let future = async move {
+        let mut stream = route_rx.into_stream();
+
+        while let Some(Some(route)) = stream.next().await {
+            let (method, param) = route.request;
+            let response = server.respond(method, param.query);
+            let _ = route.response.into_send_async(response).await;
+        }
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::spawn(future);
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(future);
+}
@@ -1,5 +1,11 @@
 //! Networking clients for communicating with the server
 
+pub use crate::protocol::any::Client as AnyClient;
+
+#[cfg(any(feature = "mem", feature = "rocksdb"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "mem", feature = "rocksdb"))))]
+pub use crate::protocol::embedded::Client as DbClient;
+
 #[cfg(feature = "http")]
 #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
 pub use crate::protocol::http::Client as HttpClient;
@@ -0,0 +1,300 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hasher;
+use std::time::Duration;
+
+/// Settings that control how the client talks to the server
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use surrealdb_rs::param::Config;
+/// use surrealdb_rs::param::Reconnect;
+/// use surrealdb_rs::protocol::Ws;
+/// use surrealdb_rs::Surreal;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb_rs::Result<()> {
+/// let config = Config::new()
+///     .connect_timeout(Duration::from_secs(10))
+///     .request_timeout(Duration::from_secs(30))
+///     .ping_interval(Duration::from_secs(10))
+///     .idle_timeout(Duration::from_secs(60))
+///     .reconnect(
+///         Reconnect::backoff(Duration::from_millis(100), Duration::from_secs(10)).max_attempts(20),
+///     );
+///
+/// let client = Surreal::connect::<Ws>("localhost:8000")
+///     .with_config(config)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) request_timeout: Option<Duration>,
+    pub(crate) ping_interval: Duration,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) max_message_size: usize,
+    pub(crate) max_frame_size: usize,
+    pub(crate) reconnect: Reconnect,
+    pub(crate) resend_reads: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    /// Creates a configuration with the default settings
+    pub const fn new() -> Self {
+        Self {
+            connect_timeout: None,
+            request_timeout: None,
+            ping_interval: Duration::from_secs(5),
+            idle_timeout: None,
+            max_message_size: 64 << 20, // 64 MiB
+            max_frame_size: 16 << 20,   // 16 MiB
+            reconnect: Reconnect::new(),
+            resend_reads: false,
+        }
+    }
+
+    /// Limits how long connecting to the server may take
+    ///
+    /// A `timeout` in the connection string takes precedence over this one.
+    #[must_use]
+    pub const fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits how long the client waits for the response to a request
+    #[must_use]
+    pub const fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how often an idle WebSocket connection pings the server
+    ///
+    /// Defaults to 5 seconds.
+    #[must_use]
+    pub const fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Treats a WebSocket connection as dead if the server sends nothing for this long
+    ///
+    /// The client then reconnects according to its [reconnect policy](Self::reconnect).
+    /// This should be longer than the [ping interval](Self::ping_interval), otherwise
+    /// healthy but quiet connections will be dropped.
+    #[must_use]
+    pub const fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum size of a WebSocket message in bytes
+    ///
+    /// Defaults to 64 MiB.
+    #[must_use]
+    pub const fn max_message_size(mut self, size: usize) -> Self {
+        self.max_message_size = size;
+        self
+    }
+
+    /// Sets the maximum size of a single WebSocket frame in bytes
+    ///
+    /// Defaults to 16 MiB.
+    #[must_use]
+    pub const fn max_frame_size(mut self, size: usize) -> Self {
+        self.max_frame_size = size;
+        self
+    }
+
+    /// Sets what the WebSocket client does when the connection drops
+    #[must_use]
+    pub const fn reconnect(mut self, policy: Reconnect) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Sends `select`, `version` and `health` requests again after reconnecting
+    ///
+    /// By default, requests that are waiting for a response when the WebSocket connection
+    /// drops fail with [`ErrorKind::ConnectionLost`](crate::ErrorKind::ConnectionLost).
+    /// These reads are safe to repeat, so with this enabled they are sent again once the
+    /// client reconnects instead. Other requests still fail as they may already have been
+    /// applied by the server.
+    #[must_use]
+    pub const fn resend_reads(mut self, enabled: bool) -> Self {
+        self.resend_reads = enabled;
+        self
+    }
+}
+
+/// What to do when the connection to the server drops
+///
+/// The first attempt to reconnect is made right away. By default the client then keeps
+/// trying, waiting one second after the first failed attempt and doubling the wait after
+/// each one that follows, up to 30 seconds. Each wait is randomly shortened by up to half
+/// so that clients which lost their connection at the same time don't all come back at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reconnect {
+    pub(crate) enabled: bool,
+    pub(crate) initial_delay: Duration,
+    pub(crate) max_delay: Duration,
+    pub(crate) max_attempts: Option<u32>,
+}
+
+impl Default for Reconnect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reconnect {
+    const fn new() -> Self {
+        Self::backoff(Duration::from_secs(1), Duration::from_secs(30))
+    }
+
+    /// Never reconnects, failing all requests once the connection drops
+    pub const fn never() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new()
+        }
+    }
+
+    /// Keeps reconnecting, waiting exponentially longer after each failed attempt
+    ///
+    /// The wait starts at `initial` and doubles each time until it reaches `max`.
+    pub const fn backoff(initial: Duration, max: Duration) -> Self {
+        Self {
+            enabled: true,
+            initial_delay: initial,
+            max_delay: max,
+            max_attempts: None,
+        }
+    }
+
+    /// Gives up after this many failed attempts to reconnect
+    #[must_use]
+    pub const fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// How long to wait before the given attempt, starting from `0`
+    ///
+    /// Returns `None` once the client should stop trying.
+    pub(crate) fn delay(&self, attempt: u32) -> Option<Duration> {
+        if !self.enabled || self.max_attempts.map_or(false, |max| attempt >= max) {
+            return None;
+        }
+        if attempt == 0 {
+            return Some(Duration::ZERO);
+        }
+        let factor = 2_u32.checked_pow(attempt - 1).unwrap_or(u32::MAX);
+        let delay = self
+            .initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        // equal jitter: keep at least half of the delay and randomise the rest
+        let half = delay / 2;
+        let jitter = half.mul_f64(random());
+        Some(half + jitter)
+    }
+}
+
+/// A random number between `0` and `1` that is good enough for jitter
+///
+/// This avoids pulling in a random number generator just for jitter. Every [`RandomState`]
+/// is keyed differently: the standard library seeds the keys from the operating system once
+/// per thread and changes them for each new instance. Hashing the same input with a new
+/// instance therefore yields a different, unpredictable number each time. The top 53 bits of
+/// the hash are used as the mantissa of the result.
+fn random() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(0);
+    (hasher.finish() >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INITIAL: Duration = Duration::from_millis(100);
+    const MAX: Duration = Duration::from_secs(1);
+
+    /// The range the delay before an attempt has to fall in
+    fn bounds(policy: &Reconnect, attempt: u32) -> (Duration, Duration) {
+        let full = (0..attempt.saturating_sub(1)).fold(policy.initial_delay, |delay, _| {
+            (delay * 2).min(policy.max_delay)
+        });
+        (full / 2, full)
+    }
+
+    #[test]
+    fn first_attempt_is_immediate() {
+        let policy = Reconnect::backoff(INITIAL, MAX);
+        assert_eq!(policy.delay(0), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn delay_doubles_up_to_the_cap() {
+        let policy = Reconnect::backoff(INITIAL, MAX);
+        for attempt in 1..10 {
+            let (min, max) = bounds(&policy, attempt);
+            let delay = policy.delay(attempt).unwrap();
+            assert!(min <= delay && delay <= max, "attempt {attempt}: {delay:?}");
+        }
+        assert_eq!(bounds(&policy, 3), (INITIAL * 2, INITIAL * 4));
+        assert_eq!(bounds(&policy, 9), (MAX / 2, MAX));
+    }
+
+    #[test]
+    fn delay_saturates_instead_of_overflowing() {
+        let policy = Reconnect::backoff(INITIAL, MAX);
+        let delay = policy.delay(u32::MAX).unwrap();
+        assert!(MAX / 2 <= delay && delay <= MAX, "{delay:?}");
+    }
+
+    #[test]
+    fn jitter_stays_within_half_of_the_delay() {
+        let policy = Reconnect::backoff(MAX, MAX);
+        let delays: Vec<Duration> = (0..100).map(|_| policy.delay(1).unwrap()).collect();
+        assert!(delays
+            .iter()
+            .all(|delay| MAX / 2 <= *delay && *delay <= MAX));
+        // the delays are spread out rather than all the same
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+    }
+
+    #[test]
+    fn random_is_between_zero_and_one() {
+        for _ in 0..1000 {
+            let random = random();
+            assert!((0.0..1.0).contains(&random), "{random}");
+        }
+    }
+
+    #[test]
+    fn max_attempts_stops_retrying() {
+        let policy = Reconnect::backoff(INITIAL, MAX).max_attempts(3);
+        assert!(policy.delay(2).is_some());
+        assert_eq!(policy.delay(3), None);
+        assert_eq!(policy.delay(4), None);
+    }
+
+    #[test]
+    fn disabled_policy_never_retries() {
+        assert_eq!(Reconnect::never().delay(0), None);
+    }
+}
@@ -0,0 +1,644 @@
+use crate::param::ser::InternalSerialization;
+use crate::ErrorKind;
+use chrono::SecondsFormat;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::de;
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::value::MapDeserializer;
+use serde::de::value::StrDeserializer;
+use serde::de::DeserializeOwned;
+use serde::de::DeserializeSeed;
+use serde::de::Deserializer as _;
+use serde::de::IntoDeserializer;
+use serde::de::Visitor;
+use serde::forward_to_deserialize_any;
+use std::collections::btree_map;
+use std::fmt;
+use std::slice;
+use surrealdb::sql::Id;
+use surrealdb::sql::Number;
+use surrealdb::sql::Thing;
+use surrealdb::sql::Value;
+
+/// Deserializes a value `T` from `SurrealDB` `Value`
+///
+/// The value is read in place, without converting it to an intermediate format first.
+/// Errors report the path of the field that failed to deserialize, for example
+/// `result[3].name.first`.
+pub fn from_value<T>(value: &Value) -> crate::Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Deserializer {
+        value,
+        path: Path::Root,
+    })
+    .map_err(|error| {
+        let path = error.path.unwrap_or_else(|| Path::Root.to_string());
+        ErrorKind::Deserialization
+            .with_message(format!("failed to deserialize `{path}`; {}", error.message))
+    })
+}
+
+/// The location of a value within the value being deserialized
+#[derive(Debug, Clone, Copy)]
+enum Path<'a> {
+    Root,
+    Index(&'a Path<'a>, usize),
+    Key(&'a Path<'a>, &'a str),
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Path::Root => write!(f, "result"),
+            Path::Index(parent, index) => write!(f, "{parent}[{index}]"),
+            Path::Key(parent, key) => write!(f, "{parent}.{key}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Error {
+    message: String,
+    path: Option<String>,
+}
+
+impl Error {
+    /// Records where the error happened, unless a nested value already did
+    fn at(mut self, path: Path<'_>) -> Self {
+        if self.path.is_none() {
+            self.path = Some(path.to_string());
+        }
+        self
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self {
+            message: msg.to_string(),
+            path: None,
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+fn unexpected(value: &Value) -> de::Unexpected<'_> {
+    match value {
+        Value::None | Value::Void | Value::Null => de::Unexpected::Unit,
+        Value::False => de::Unexpected::Bool(false),
+        Value::True => de::Unexpected::Bool(true),
+        Value::Number(Number::Int(int)) => de::Unexpected::Signed(*int),
+        Value::Number(Number::Float(float)) => de::Unexpected::Float(*float),
+        Value::Strand(strand) => de::Unexpected::Str(&strand.0),
+        Value::Array(..) => de::Unexpected::Seq,
+        Value::Object(..) => de::Unexpected::Map,
+        _ => de::Unexpected::Other("a SurrealDB value"),
+    }
+}
+
+struct Deserializer<'de, 'p> {
+    value: &'de Value,
+    path: Path<'p>,
+}
+
+impl<'de, 'p> Deserializer<'de, 'p> {
+    fn any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::None | Value::Void | Value::Null => visitor.visit_unit(),
+            Value::False => visitor.visit_bool(false),
+            Value::True => visitor.visit_bool(true),
+            Value::Number(Number::Int(int)) => visitor.visit_i64(*int),
+            Value::Number(Number::Float(float)) => visitor.visit_f64(*float),
+            // keeps the precision for types that can be parsed from strings
+            Value::Number(Number::Decimal(decimal)) => visitor.visit_string(decimal.to_string()),
+            Value::Strand(strand) => visitor.visit_borrowed_str(&strand.0),
+            Value::Duration(duration) => visitor.visit_string(duration.to_string()),
+            Value::Datetime(datetime) => {
+                visitor.visit_string(datetime.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            Value::Uuid(uuid) => visitor.visit_string(uuid.0.to_string()),
+            Value::Table(table) => visitor.visit_borrowed_str(&table.0),
+            Value::Array(array) => visitor.visit_seq(SeqAccess {
+                iter: array.0.iter().enumerate(),
+                path: self.path,
+            }),
+            Value::Object(object) => visitor.visit_map(MapAccess {
+                iter: object.0.iter(),
+                value: None,
+                path: self.path,
+            }),
+            value => visitor.visit_string(value.to_string()),
+        }
+    }
+
+    fn number<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Number(Number::Decimal(decimal)) => visit_decimal(*decimal, visitor),
+            _ => self.any(visitor),
+        }
+    }
+
+    fn option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::None | Value::Void | Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    /// An enum variant holding this value
+    fn variant(&self, variant: &'static str) -> Variant<'de, 'p> {
+        Variant {
+            variant,
+            content: Some(Deserializer {
+                value: self.value,
+                path: self.path,
+            }),
+        }
+    }
+
+    fn structure<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (name, self.value) {
+            // how `std::time::Duration` deserializes itself
+            ("Duration", Value::Duration(duration)) => {
+                let fields = [
+                    ("secs", duration.0.as_secs()),
+                    ("nanos", u64::from(duration.0.subsec_nanos())),
+                ];
+                visitor.visit_map(MapDeserializer::new(fields.into_iter()))
+            }
+            ("Thing", Value::Thing(thing)) => visitor.visit_map(ThingAccess { thing, field: 0 }),
+            _ => self.any(visitor),
+        }
+    }
+
+    fn enumeration<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let unit = Variant::unit;
+        let newtype = |variant| self.variant(variant);
+        match (name, self.value) {
+            // `SurrealDB` values deserialize from the same values they were serialized from
+            ("Value", Value::None) => visitor.visit_enum(unit("None")),
+            ("Value", Value::Void) => visitor.visit_enum(unit("Void")),
+            ("Value", Value::Null) => visitor.visit_enum(unit("Null")),
+            ("Value", Value::False) => visitor.visit_enum(unit("False")),
+            ("Value", Value::True) => visitor.visit_enum(unit("True")),
+            ("Value", Value::Number(..)) => visitor.visit_enum(newtype("Number")),
+            ("Value", Value::Strand(..)) => visitor.visit_enum(newtype("Strand")),
+            ("Value", Value::Duration(..)) => visitor.visit_enum(newtype("Duration")),
+            ("Value", Value::Datetime(..)) => visitor.visit_enum(newtype("Datetime")),
+            ("Value", Value::Uuid(..)) => visitor.visit_enum(newtype("Uuid")),
+            ("Value", Value::Array(..)) => visitor.visit_enum(newtype("Array")),
+            ("Value", Value::Object(..)) => visitor.visit_enum(newtype("Object")),
+            ("Value", Value::Table(..)) => visitor.visit_enum(newtype("Table")),
+            ("Value", Value::Thing(..)) => visitor.visit_enum(newtype("Thing")),
+            // the other variants don't expose their structure, so `SurrealDB` reads them back
+            // from the form it exchanges them with the server in
+            ("Value", value) => {
+                let bytes = {
+                    let _guard = InternalSerialization::begin();
+                    serde_pack::to_vec(value).map_err(<Error as de::Error>::custom)?
+                };
+                serde_pack::Deserializer::new(bytes.as_slice())
+                    .deserialize_enum(name, variants, visitor)
+                    .map_err(de::Error::custom)
+            }
+            ("Number", Value::Number(Number::Int(..))) => visitor.visit_enum(newtype("Int")),
+            ("Number", Value::Number(Number::Float(..))) => visitor.visit_enum(newtype("Float")),
+            ("Number", Value::Number(Number::Decimal(..))) => {
+                visitor.visit_enum(newtype("Decimal"))
+            }
+            // externally tagged enums, the way they are serialized
+            (_, Value::Strand(strand)) => visitor.visit_enum(unit(&strand.0)),
+            (_, Value::Object(object)) if object.0.len() == 1 => {
+                let (variant, value) = object.0.iter().next().unwrap();
+                visitor.visit_enum(Variant {
+                    variant,
+                    content: Some(Deserializer {
+                        value,
+                        path: Path::Key(&self.path, variant),
+                    }),
+                })
+            }
+            (_, value) => Err(de::Error::invalid_type(unexpected(value), &"an enum")),
+        }
+    }
+}
+
+fn visit_decimal<'de, V>(decimal: Decimal, visitor: V) -> Result<V::Value, Error>
+where
+    V: Visitor<'de>,
+{
+    if decimal.fract().is_zero() {
+        if let Some(int) = decimal.to_i64() {
+            return visitor.visit_i64(int);
+        }
+        if let Some(int) = decimal.to_u64() {
+            return visitor.visit_u64(int);
+        }
+        if let Some(int) = decimal.to_i128() {
+            return visitor.visit_i128(int);
+        }
+    }
+    match decimal.to_f64() {
+        Some(float) => visitor.visit_f64(float),
+        None => visitor.visit_string(decimal.to_string()),
+    }
+}
+
+macro_rules! deserialize {
+    ($($method:ident => $inner:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                let path = self.path;
+                self.$inner(visitor).map_err(|error| error.at(path))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de, '_> {
+    type Error = Error;
+
+    deserialize! {
+        deserialize_any => any,
+        deserialize_i8 => number,
+        deserialize_i16 => number,
+        deserialize_i32 => number,
+        deserialize_i64 => number,
+        deserialize_i128 => number,
+        deserialize_u8 => number,
+        deserialize_u16 => number,
+        deserialize_u32 => number,
+        deserialize_u64 => number,
+        deserialize_u128 => number,
+        deserialize_f32 => number,
+        deserialize_f64 => number,
+        deserialize_option => option,
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path;
+        visitor
+            .visit_newtype_struct(self)
+            .map_err(|error| error.at(path))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path;
+        self.structure(name, visitor)
+            .map_err(|error| error.at(path))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path;
+        self.enumeration(name, variants, visitor)
+            .map_err(|error| error.at(path))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        // values are read in place rather than from a stream, so an ignored value doesn't need
+        // to be walked to get past it; unit is the cheapest thing to hand the visitor, which
+        // discards whatever it's given
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+struct SeqAccess<'de, 'p> {
+    iter: std::iter::Enumerate<slice::Iter<'de, Value>>,
+    path: Path<'p>,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess<'de, '_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((index, value)) => seed
+                .deserialize(Deserializer {
+                    value,
+                    path: Path::Index(&self.path, index),
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapAccess<'de, 'p> {
+    iter: btree_map::Iter<'de, String, Value>,
+    value: Option<(&'de str, &'de Value)>,
+    path: Path<'p>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'de, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(BorrowedStrDeserializer::new(key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some((key, value)) => seed.deserialize(Deserializer {
+                value,
+                path: Path::Key(&self.path, key),
+            }),
+            None => Err(de::Error::custom("map value requested before its key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Presents a record ID as the `tb` and `id` fields it's made of
+struct ThingAccess<'de> {
+    thing: &'de Thing,
+    field: u8,
+}
+
+impl<'de> de::MapAccess<'de> for ThingAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match self.field {
+            0 => "tb",
+            1 => "id",
+            _ => return Ok(None),
+        };
+        seed.deserialize(StrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.field += 1;
+        match self.field {
+            1 => seed.deserialize(BorrowedStrDeserializer::new(&self.thing.tb)),
+            _ => seed.deserialize(IdDeserializer(&self.thing.id)),
+        }
+    }
+}
+
+struct IdDeserializer<'de>(&'de Id);
+
+impl<'de> de::Deserializer<'de> for IdDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Id::Number(number) => visitor.visit_i64(*number),
+            Id::String(string) => visitor.visit_borrowed_str(string),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for IdDeserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = match self.0 {
+            Id::Number(..) => "Number",
+            Id::String(..) => "String",
+        };
+        let variant = seed.deserialize(variant.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for IdDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"a unit variant",
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"a tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"a struct variant",
+        ))
+    }
+}
+
+struct Variant<'de, 'p> {
+    variant: &'de str,
+    content: Option<Deserializer<'de, 'p>>,
+}
+
+impl<'de> Variant<'de, '_> {
+    fn unit(variant: &'de str) -> Self {
+        Self {
+            variant,
+            content: None,
+        }
+    }
+}
+
+impl<'de, 'p> de::EnumAccess<'de> for Variant<'de, 'p> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(self.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Variant<'de, '_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.content {
+            None => Ok(()),
+            Some(content) => de::Deserialize::deserialize(content),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.content {
+            Some(content) => seed.deserialize(content),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.content {
+            Some(content) => de::Deserializer::deserialize_seq(content, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.content {
+            Some(content) => de::Deserializer::deserialize_map(content, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a struct variant",
+            )),
+        }
+    }
+}
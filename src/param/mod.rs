@@ -1,29 +1,42 @@
 //! Parameter types
 
+mod config;
 mod credentials;
+mod de;
 mod jwt;
+mod notification;
 mod query;
+mod query_result;
 mod resource;
+mod ser;
 mod server_addrs;
+mod store;
 
 use crate::Result;
 use dmp::Diff;
-use serde::de::DeserializeOwned;
+use flume::Sender;
 use serde::Serialize;
-use serde_json::json;
+#[cfg(feature = "http")]
 use serde_json::Value as JsonValue;
 use std::collections::BTreeMap;
-#[cfg(feature = "http")]
+#[cfg(any(feature = "http", feature = "rocksdb"))]
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
+use std::time::Duration;
 use surrealdb::sql;
 use surrealdb::sql::Value;
 
+pub use config::*;
 pub use credentials::*;
+pub use de::*;
 pub use jwt::*;
+pub use notification::*;
 pub use query::*;
+pub use query_result::*;
 pub use resource::*;
+pub use ser::*;
 pub use server_addrs::*;
+pub use store::*;
 
 /// Record ID
 pub type RecordId = sql::Thing;
@@ -48,7 +61,7 @@ enum InnerOp<'a, T> {
 ///
 /// [JSON Patch]: https://jsonpatch.com/
 #[derive(Debug)]
-pub struct PatchOp(pub(crate) Value);
+pub struct PatchOp(pub(crate) Result<Value>);
 
 impl PatchOp {
     /// Adds a value to an object or inserts it into an array.
@@ -69,8 +82,7 @@ impl PatchOp {
     where
         T: Serialize,
     {
-        let value = from_json(json!(InnerOp::Add { path, value }));
-        Self(value)
+        Self(to_value(InnerOp::Add { path, value }))
     }
 
     /// Removes a value from an object or array.
@@ -93,8 +105,7 @@ impl PatchOp {
     /// ```
     #[must_use]
     pub fn remove(path: &str) -> Self {
-        let value = from_json(json!(UnitOp::Remove { path }));
-        Self(value)
+        Self(to_value(UnitOp::Remove { path }))
     }
 
     /// Replaces a value.
@@ -113,69 +124,79 @@ impl PatchOp {
     where
         T: Serialize,
     {
-        let value = from_json(json!(InnerOp::Replace { path, value }));
-        Self(value)
+        Self(to_value(InnerOp::Replace { path, value }))
     }
 
     /// Changes a value
     #[must_use]
     pub fn change(path: &str, diff: Diff) -> Self {
-        let value = from_json(json!(UnitOp::Change {
+        Self(to_value(UnitOp::Change {
             path,
             value: diff.text,
-        }));
-        Self(value)
+        }))
     }
 }
 
 /// Holds the parameters given to the caller
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Param {
     pub(crate) query: Vec<sql::Value>,
-    #[cfg(feature = "http")]
+    #[cfg(any(feature = "http", feature = "rocksdb"))]
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) file: Option<PathBuf>,
+    pub(crate) notification_sender: Option<Sender<Result<Notification<sql::Value>>>>,
+    /// A timeout set on the request, replacing the client-wide default
+    pub(crate) timeout: Option<Duration>,
 }
 
 impl Param {
     pub(crate) fn new(query: Vec<sql::Value>) -> Self {
         Self {
             query,
-            #[cfg(feature = "http")]
+            #[cfg(any(feature = "http", feature = "rocksdb"))]
             #[cfg(not(target_arch = "wasm32"))]
             file: None,
+            notification_sender: None,
+            timeout: None,
         }
     }
 
-    #[cfg(feature = "http")]
+    #[cfg(any(feature = "http", feature = "rocksdb"))]
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) fn file(file: PathBuf) -> Self {
         Self {
-            query: Vec::new(),
             file: Some(file),
+            ..Self::new(Vec::new())
+        }
+    }
+
+    pub(crate) fn live(
+        query: Vec<sql::Value>,
+        sender: Sender<Result<Notification<sql::Value>>>,
+    ) -> Self {
+        Self {
+            notification_sender: Some(sender),
+            ..Self::new(query)
         }
     }
+
+    /// Sets the timeout of the request, if it has one of its own
+    pub(crate) fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
 }
 
 /// The database response sent from the router to the caller
 #[derive(Debug)]
 pub enum DbResponse {
     /// The response sent for the `query` method
-    Query(Vec<Result<Vec<sql::Value>>>),
+    Query(crate::Response),
     /// The response sent for any method except `query`
     Other(sql::Value),
 }
 
-/// Deserializes a value `T` from `SurrealDB` `Value`
-pub fn from_value<T>(value: &sql::Value) -> Result<T>
-where
-    T: DeserializeOwned,
-{
-    let bytes = serde_pack::to_vec(&value)?;
-    let response = serde_pack::from_slice(&bytes)?;
-    Ok(response)
-}
-
+#[cfg(feature = "http")]
 pub(crate) fn from_json(json: JsonValue) -> sql::Value {
     match json {
         JsonValue::Null => sql::Value::None,
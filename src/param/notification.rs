@@ -0,0 +1,28 @@
+use serde::Deserialize;
+use serde::Serialize;
+use surrealdb::sql::Uuid;
+
+/// The action that triggered a live query notification
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+#[non_exhaustive]
+pub enum Action {
+    /// A record was created
+    Create,
+    /// A record was updated
+    Update,
+    /// A record was deleted
+    Delete,
+}
+
+/// A live query notification
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Notification<R> {
+    /// The ID of the live query that produced this notification
+    pub query_id: Uuid,
+    /// The action that triggered this notification
+    pub action: Action,
+    /// The record the action was performed on
+    pub data: R,
+}
@@ -0,0 +1,90 @@
+use crate::param::from_value;
+use crate::ErrorKind;
+use crate::Response;
+use crate::Result;
+use serde::de::DeserializeOwned;
+use std::mem;
+use surrealdb::sql::Array;
+use surrealdb::sql::Object;
+use surrealdb::sql::Value;
+
+/// A trait for taking the deserialized result of a statement out of a query response
+pub trait QueryResult<Response>: Sized {
+    /// Takes the result of a statement out of a query response and deserializes it
+    fn query_result(self, response: &mut crate::Response) -> Result<Response>;
+}
+
+impl<T> QueryResult<Vec<T>> for usize
+where
+    T: DeserializeOwned,
+{
+    fn query_result(self, response: &mut Response) -> Result<Vec<T>> {
+        // deserializing the records together reports the index of the one that failed
+        from_value(&Value::Array(Array(take(response, self)?)))
+    }
+}
+
+impl<T> QueryResult<Option<T>> for usize
+where
+    T: DeserializeOwned,
+{
+    fn query_result(self, response: &mut Response) -> Result<Option<T>> {
+        match &take(response, self)?[..] {
+            [] => Ok(None),
+            [value] => from_value(value).map(Some),
+            values => Err(too_many(self, values.len())),
+        }
+    }
+}
+
+impl<T> QueryResult<Vec<T>> for (usize, &str)
+where
+    T: DeserializeOwned,
+{
+    fn query_result(self, response: &mut Response) -> Result<Vec<T>> {
+        let (index, key) = self;
+        let values = take(response, index)?
+            .into_iter()
+            .filter_map(|value| field(value, key))
+            .collect();
+        from_value(&Value::Array(Array(values)))
+    }
+}
+
+impl<T> QueryResult<Option<T>> for (usize, &str)
+where
+    T: DeserializeOwned,
+{
+    fn query_result(self, response: &mut Response) -> Result<Option<T>> {
+        let (index, key) = self;
+        let mut values = take(response, index)?;
+        match values.len() {
+            0 => Ok(None),
+            1 => match field(values.remove(0), key) {
+                Some(value) => from_value(&value).map(Some),
+                None => Ok(None),
+            },
+            len => Err(too_many(index, len)),
+        }
+    }
+}
+
+fn take(response: &mut Response, index: usize) -> Result<Vec<Value>> {
+    match response.results.get_mut(index) {
+        Some(result) => mem::replace(result, Ok(Vec::new())),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn field(value: Value, key: &str) -> Option<Value> {
+    match value {
+        Value::Object(Object(mut object)) => object.remove(key),
+        _ => None,
+    }
+}
+
+fn too_many(index: usize, len: usize) -> crate::Error {
+    ErrorKind::Deserialization.with_message(format!(
+        "expected at most one record from statement {index} but found {len}; take a `Vec` instead"
+    ))
+}
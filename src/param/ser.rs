@@ -0,0 +1,578 @@
+use crate::Error;
+use crate::ErrorKind;
+use crate::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::ser;
+use serde::ser::Serialize;
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use surrealdb::sql;
+use surrealdb::sql::serde::beg_internal_serialization;
+use surrealdb::sql::serde::end_internal_serialization;
+use surrealdb::sql::Id;
+use surrealdb::sql::Number;
+use surrealdb::sql::Strand;
+use surrealdb::sql::Thing;
+use surrealdb::sql::Value;
+
+/// Serializes a value `T` into a `SurrealDB` [`Value`]
+///
+/// Unlike going through JSON, this keeps the types `SurrealDB` knows about. Values of
+/// [`sql::Value`], including geometries, ranges and the other variants JSON can't express, are
+/// passed through as they are. So are record IDs, datetimes, durations, UUIDs and numbers
+/// of the `sql` types. 128-bit integers that don't fit in an `i64` become decimals. `SurrealDB`
+/// has no bytes type so bytes are serialized as an array of numbers.
+///
+/// The `sql` types are recognised by their Rust types, so your own types are serialized as
+/// usual even if they share a name with one of them. The types they wrap are not recognised
+/// on their own. `chrono` datetimes, `uuid` UUIDs and `rust_decimal` decimals serialize
+/// themselves as strings, so wrap them in [`sql::Datetime`], [`sql::Uuid`] and
+/// [`Number::Decimal`] to send them as datetimes, UUIDs and numbers.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use surrealdb::sql;
+/// use surrealdb::sql::Value;
+/// use surrealdb_rs::param::to_value;
+///
+/// # fn main() -> surrealdb_rs::Result<()> {
+/// let value = to_value(sql::Duration::from(Duration::from_secs(90)))?;
+/// assert!(matches!(value, Value::Duration(..)));
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_value<T>(value: T) -> Result<Value>
+where
+    T: Serialize,
+{
+    // `SurrealDB` types only expose their structure when serialized internally
+    let _guard = InternalSerialization::begin();
+    value.serialize(Serializer)
+}
+
+/// Makes `SurrealDB` types expose their structure for as long as it's alive
+pub(crate) struct InternalSerialization;
+
+impl InternalSerialization {
+    pub(crate) fn begin() -> Self {
+        beg_internal_serialization();
+        Self
+    }
+}
+
+impl Drop for InternalSerialization {
+    fn drop(&mut self) {
+        end_internal_serialization();
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        ErrorKind::Serialization.with_context(msg)
+    }
+}
+
+fn unsupported(kind: &str) -> Error {
+    ErrorKind::Serialization.with_message(format!("{kind} values can't be sent to the server"))
+}
+
+fn int(value: impl TryInto<i64> + ToString) -> Result<Value> {
+    let string = value.to_string();
+    match value.try_into() {
+        Ok(int) => Ok(int.into()),
+        Err(..) => decimal(&string),
+    }
+}
+
+fn decimal(value: &str) -> Result<Value> {
+    match Decimal::from_str(value) {
+        Ok(decimal) => Ok(Value::Number(Number::Decimal(decimal))),
+        Err(error) => Err(ErrorKind::Serialization.with_message(format!(
+            "failed to convert `{value}` into a decimal; {error}"
+        ))),
+    }
+}
+
+/// Converts the fields `std::time::Duration` serializes itself as
+fn duration(value: Value) -> Result<Value> {
+    let mut object = match value {
+        Value::Object(sql::Object(object)) if has_fields(&object, &["secs", "nanos"]) => object,
+        _ => return Err(unsupported("malformed duration")),
+    };
+    match (object.remove("secs"), object.remove("nanos")) {
+        (Some(Value::Number(Number::Int(secs))), Some(Value::Number(Number::Int(nanos)))) => {
+            match (u64::try_from(secs), u32::try_from(nanos)) {
+                (Ok(secs), Ok(nanos)) => Ok(Value::Duration(Duration::new(secs, nanos).into())),
+                _ => Err(unsupported("negative duration")),
+            }
+        }
+        _ => Err(unsupported("non-integer duration")),
+    }
+}
+
+fn string(value: Value) -> Result<String> {
+    match value {
+        Value::Strand(Strand(string)) => Ok(string),
+        value => {
+            Err(ErrorKind::Serialization.with_message(format!("expected a string, found {value}")))
+        }
+    }
+}
+
+fn has_fields(object: &BTreeMap<String, Value>, fields: &[&str]) -> bool {
+    object.len() == fields.len() && fields.iter().all(|field| object.contains_key(*field))
+}
+
+/// Whether a value being serialized is of type `U`
+///
+/// Serde only hands serializers the names types choose to serialize under, which any type
+/// can share. The `sql` types are told apart from other types by what they wrap instead.
+/// Serializers are handed values without a `'static` bound, which `typeid` doesn't need.
+fn is<T: ?Sized, U: 'static>() -> bool {
+    typeid::of::<T>() == TypeId::of::<U>()
+}
+
+/// Converts a variant of [`sql::Value`] by having `SurrealDB` deserialize it
+///
+/// This supports every variant, including those that don't expose their structure to
+/// serializers, the same way values are exchanged with the server.
+fn sql_value<T>(index: u32, variant: &'static str, value: &T) -> Result<Value>
+where
+    T: ?Sized + Serialize,
+{
+    let bytes = serde_pack::to_vec(&ValueVariant {
+        index,
+        variant,
+        value,
+    })?;
+    Ok(serde_pack::from_slice(&bytes)?)
+}
+
+struct ValueVariant<'a, T: ?Sized> {
+    index: u32,
+    variant: &'static str,
+    value: &'a T,
+}
+
+impl<T> Serialize for ValueVariant<'_, T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_newtype_variant("Value", self.index, self.variant, self.value)
+    }
+}
+
+/// Wraps the value of an enum variant the same way `serde_json` does
+fn wrap(variant: &str, value: Value) -> Value {
+    let mut object = BTreeMap::new();
+    object.insert(variant.to_owned(), value);
+    object.into()
+}
+
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeVariant<SerializeStruct>;
+
+    fn serialize_bool(self, value: bool) -> Result<Value> {
+        Ok(value.into())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Value> {
+        Ok(i64::from(value).into())
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Value> {
+        Ok(i64::from(value).into())
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Value> {
+        Ok(i64::from(value).into())
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Value> {
+        Ok(value.into())
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Value> {
+        int(value)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Value> {
+        Ok(i64::from(value).into())
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Value> {
+        Ok(i64::from(value).into())
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Value> {
+        Ok(i64::from(value).into())
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Value> {
+        int(value)
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Value> {
+        int(value)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Value> {
+        Ok(f64::from(value).into())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Value> {
+        Ok(value.into())
+    }
+
+    fn serialize_char(self, value: char) -> Result<Value> {
+        Ok(value.to_string().into())
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Value> {
+        Ok(value.into())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value> {
+        let bytes: Vec<Value> = value.iter().map(|byte| i64::from(*byte).into()).collect();
+        Ok(bytes.into())
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value> {
+        match (name, variant) {
+            ("Value", "None") => Ok(Value::None),
+            ("Value", "Null") => Ok(Value::Null),
+            ("Value", "False") => Ok(false.into()),
+            ("Value", "True") => Ok(true.into()),
+            _ => Ok(variant.into()),
+        }
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(self)?;
+        match name {
+            "Datetime" if is::<T, DateTime<Utc>>() => {
+                let string = string(value)?;
+                match DateTime::parse_from_rfc3339(&string) {
+                    Ok(datetime) => Ok(Value::Datetime(datetime.with_timezone(&Utc).into())),
+                    Err(error) => Err(ErrorKind::Serialization
+                        .with_message(format!("invalid datetime `{string}`; {error}"))),
+                }
+            }
+            "Uuid" if is::<T, uuid::Uuid>() => {
+                let string = string(value)?;
+                match uuid::Uuid::parse_str(&string) {
+                    Ok(uuid) => Ok(Value::Uuid(uuid.into())),
+                    Err(error) => Err(ErrorKind::Serialization
+                        .with_message(format!("invalid UUID `{string}`; {error}"))),
+                }
+            }
+            "Duration" if is::<T, Duration>() => duration(value),
+            _ => Ok(value),
+        }
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        match (name, variant) {
+            ("Value", "Bool" | "Strand" | "Array" | "Object") => value.serialize(self),
+            // an enum of another type that happens to be called `Value` is tagged as usual
+            ("Value", _) => sql_value(index, variant, value)
+                .or_else(|_| Ok(wrap(variant, value.serialize(self)?))),
+            ("Number", "Int") if is::<T, i64>() => value.serialize(self),
+            ("Number", "Float") if is::<T, f64>() => value.serialize(self),
+            ("Number", "Decimal") if is::<T, Decimal>() => {
+                decimal(&string(value.serialize(self)?)?)
+            }
+            ("Id", "Number") if is::<T, i64>() => value.serialize(self),
+            ("Id", "String") if is::<T, String>() => value.serialize(self),
+            _ => Ok(wrap(variant, value.serialize(self)?)),
+        }
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray> {
+        Ok(SerializeArray(Vec::with_capacity(len.unwrap_or_default())))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeArray>> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeMap> {
+        Ok(SerializeMap {
+            object: BTreeMap::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<SerializeStruct> {
+        Ok(SerializeStruct {
+            name,
+            object: BTreeMap::new(),
+            record_id: false,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeStruct>> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_struct("", len)?,
+        })
+    }
+}
+
+struct SerializeArray(Vec<Value>);
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.0.into())
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeMap {
+    object: BTreeMap<String, Value>,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = match key.serialize(Serializer)? {
+            Value::Strand(Strand(key)) => key,
+            Value::Number(number) => number.to_string(),
+            key => {
+                return Err(ErrorKind::Serialization
+                    .with_message(format!("object keys must be strings, found {key}")));
+            }
+        };
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self.key.take() {
+            Some(key) => {
+                self.object.insert(key, value.serialize(Serializer)?);
+                Ok(())
+            }
+            None => {
+                Err(ErrorKind::Serialization.with_message("map value serialized before its key"))
+            }
+        }
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(self.object.into())
+    }
+}
+
+struct SerializeStruct {
+    name: &'static str,
+    object: BTreeMap<String, Value>,
+    /// Whether the struct has an `id` field holding the ID part of a record ID
+    record_id: bool,
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if key == "id" && is::<T, Id>() {
+            self.record_id = true;
+        }
+        self.object
+            .insert(key.to_owned(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<Value> {
+        match self.name {
+            "Thing" if self.record_id && has_fields(&self.object, &["tb", "id"]) => {
+                let tb = string(self.object.remove("tb").unwrap_or_default())?;
+                let id = match self.object.remove("id") {
+                    Some(Value::Number(Number::Int(id))) => Id::Number(id),
+                    Some(Value::Strand(Strand(id))) => Id::String(id),
+                    _ => return Err(unsupported("record ID")),
+                };
+                Ok(Value::Thing(Thing { tb, id }))
+            }
+            _ => Ok(self.object.into()),
+        }
+    }
+}
+
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        let value = ser::SerializeSeq::end(self.inner)?;
+        Ok(wrap(self.variant, value))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeStruct> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        let value = ser::SerializeStruct::end(self.inner)?;
+        Ok(wrap(self.variant, value))
+    }
+}
@@ -0,0 +1,58 @@
+use crate::net::DbClient;
+use crate::param::ToServerAddrs;
+use crate::protocol::File;
+use crate::ErrorKind;
+use crate::Result;
+use crate::ServerAddrs;
+use std::env;
+use std::path::Path;
+use std::path::PathBuf;
+use url::Url;
+
+fn server_addrs(path: &Path) -> Result<ServerAddrs> {
+    let path = env::current_dir()?.join(path);
+    let endpoint = Url::from_file_path(&path).map_err(|()| {
+        ErrorKind::InvalidParams
+            .with_message(format!("`{}` is not a valid database path", path.display()))
+    })?;
+    Ok(ServerAddrs {
+        endpoint,
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        tls_config: None,
+        config: Default::default(),
+        #[cfg(feature = "test-util")]
+        harness: None,
+    })
+}
+
+impl ToServerAddrs<File> for &str {
+    type Client = DbClient;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        server_addrs(Path::new(self))
+    }
+}
+
+impl ToServerAddrs<File> for String {
+    type Client = DbClient;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        server_addrs(Path::new(&self))
+    }
+}
+
+impl ToServerAddrs<File> for &Path {
+    type Client = DbClient;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        server_addrs(self)
+    }
+}
+
+impl ToServerAddrs<File> for PathBuf {
+    type Client = DbClient;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        server_addrs(&self)
+    }
+}
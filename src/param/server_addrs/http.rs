@@ -17,6 +17,9 @@ impl ToServerAddrs<Http> for &str {
             endpoint: Url::parse(&format!("http://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -29,6 +32,9 @@ impl ToServerAddrs<Http> for SocketAddr {
             endpoint: Url::parse(&format!("http://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -41,6 +47,9 @@ impl ToServerAddrs<Http> for String {
             endpoint: Url::parse(&format!("http://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -53,6 +62,9 @@ impl ToServerAddrs<Https> for &str {
             endpoint: Url::parse(&format!("https://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -65,6 +77,9 @@ impl ToServerAddrs<Https> for SocketAddr {
             endpoint: Url::parse(&format!("https://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -77,6 +92,9 @@ impl ToServerAddrs<Https> for String {
             endpoint: Url::parse(&format!("https://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
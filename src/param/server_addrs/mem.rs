@@ -0,0 +1,21 @@
+use crate::net::DbClient;
+use crate::param::ToServerAddrs;
+use crate::protocol::Mem;
+use crate::Result;
+use crate::ServerAddrs;
+use url::Url;
+
+impl ToServerAddrs<Mem> for () {
+    type Client = DbClient;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        Ok(ServerAddrs {
+            endpoint: Url::parse("mem://")?,
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
+        })
+    }
+}
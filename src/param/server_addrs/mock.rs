@@ -0,0 +1,30 @@
+use crate::mock::Client;
+use crate::mock::Mock;
+use crate::mock::MockServer;
+use crate::param::Harness;
+use crate::param::ToServerAddrs;
+use crate::Result;
+use crate::ServerAddrs;
+use url::Url;
+
+impl ToServerAddrs<Mock> for MockServer {
+    type Client = Client;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        Ok(ServerAddrs {
+            endpoint: Url::parse("mock://")?,
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            tls_config: None,
+            config: Default::default(),
+            harness: Some(Harness::Mock(self)),
+        })
+    }
+}
+
+impl ToServerAddrs<Mock> for &MockServer {
+    type Client = Client;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        self.clone().to_server_addrs()
+    }
+}
@@ -1,10 +1,30 @@
+mod any;
+#[cfg(feature = "rocksdb")]
+mod file;
 #[cfg(feature = "http")]
 mod http;
+#[cfg(feature = "mem")]
+mod mem;
+#[cfg(feature = "test-util")]
+mod mock;
+mod options;
+#[cfg(feature = "test-util")]
+#[cfg(not(target_arch = "wasm32"))]
+mod record;
 #[cfg(feature = "ws")]
 mod ws;
 
+pub use any::Env;
+pub(crate) use options::*;
+
+#[cfg(feature = "test-util")]
+use crate::mock::MockServer;
+use crate::param::Config;
 use crate::Connection;
 use crate::Result;
+#[cfg(feature = "test-util")]
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
 use url::Url;
 
 /// TLS Configuration
@@ -22,12 +42,26 @@ pub enum Tls {
     Rust(rustls::ClientConfig),
 }
 
+/// Test utilities to route a connection through
+#[cfg(feature = "test-util")]
+#[derive(Debug)]
+pub(crate) enum Harness {
+    /// Answer requests using a mock server
+    Mock(MockServer),
+    /// Record requests and responses into a fixture file
+    #[cfg(not(target_arch = "wasm32"))]
+    Record(PathBuf),
+}
+
 /// A server address used to connect to the server
 #[derive(Debug)]
 pub struct ServerAddrs {
     pub(crate) endpoint: Url,
     #[cfg(any(feature = "native-tls", feature = "rustls"))]
     pub(crate) tls_config: Option<Tls>,
+    pub(crate) config: Config,
+    #[cfg(feature = "test-util")]
+    pub(crate) harness: Option<Harness>,
 }
 
 /// A trait for converting inputs to a server address object
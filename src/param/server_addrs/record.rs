@@ -0,0 +1,25 @@
+use crate::fixture::Client;
+use crate::fixture::Record;
+use crate::param::DbResponse;
+use crate::param::Harness;
+use crate::param::ToServerAddrs;
+use crate::Connection;
+use crate::Result;
+use crate::ServerAddrs;
+use std::path::Path;
+
+impl<S, A, P> ToServerAddrs<Record<S>> for (A, P)
+where
+    A: ToServerAddrs<S>,
+    A::Client: Connection<Response = Result<DbResponse>>,
+    P: AsRef<Path>,
+{
+    type Client = Client<A::Client>;
+
+    fn to_server_addrs(self) -> Result<ServerAddrs> {
+        let (address, path) = self;
+        let mut address = address.to_server_addrs()?;
+        address.harness = Some(Harness::Record(path.as_ref().to_owned()));
+        Ok(address)
+    }
+}
@@ -17,6 +17,9 @@ impl ToServerAddrs<Ws> for &str {
             endpoint: Url::parse(&format!("ws://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -29,6 +32,9 @@ impl ToServerAddrs<Ws> for SocketAddr {
             endpoint: Url::parse(&format!("ws://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -41,6 +47,9 @@ impl ToServerAddrs<Ws> for String {
             endpoint: Url::parse(&format!("ws://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -53,6 +62,9 @@ impl ToServerAddrs<Wss> for &str {
             endpoint: Url::parse(&format!("wss://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -65,6 +77,9 @@ impl ToServerAddrs<Wss> for SocketAddr {
             endpoint: Url::parse(&format!("wss://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
@@ -77,6 +92,9 @@ impl ToServerAddrs<Wss> for String {
             endpoint: Url::parse(&format!("wss://{self}"))?,
             #[cfg(any(feature = "native-tls", feature = "rustls"))]
             tls_config: None,
+            config: Default::default(),
+            #[cfg(feature = "test-util")]
+            harness: None,
         })
     }
 }
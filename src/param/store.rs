@@ -0,0 +1,135 @@
+use crate::param::ser::InternalSerialization;
+use crate::param::Jwt;
+use crate::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::Serializer;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::ErrorKind as IoErrorKind;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use surrealdb::sql::Value;
+
+/// The parts of a session that can be restored after the client restarts
+///
+/// The credentials given to `signin` are never saved, only the token the server returned.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SavedSession {
+    /// The token the client is authenticated with
+    pub token: Option<Jwt>,
+    /// The namespace in use
+    pub namespace: Option<String>,
+    /// The database in use
+    pub database: Option<String>,
+    /// The parameters assigned with `set`
+    #[serde(serialize_with = "serialize_vars")]
+    pub vars: BTreeMap<String, Value>,
+}
+
+/// Saves parameters in the form `SurrealDB` uses internally, which is the only one it can
+/// read them back from
+fn serialize_vars<S>(
+    vars: &BTreeMap<String, Value>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let _guard = InternalSerialization::begin();
+    vars.serialize(serializer)
+}
+
+/// Where the client keeps its session so that it can be restored after a restart
+///
+/// See [`Surreal::restore_session`](crate::Surreal::restore_session).
+pub trait SessionStore: Debug + Send + Sync {
+    /// Loads the saved session, if there is one
+    fn load(&self) -> Result<Option<SavedSession>>;
+
+    /// Saves the current session, replacing the one saved before
+    fn save(&self, session: &SavedSession) -> Result<()>;
+}
+
+/// Keeps the session in memory
+///
+/// Clones share the same session, which makes this useful for handing a session over from
+/// one client to another and for testing.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore(Arc<Mutex<Option<SavedSession>>>);
+
+impl MemoryStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self) -> Result<Option<SavedSession>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    fn save(&self, session: &SavedSession) -> Result<()> {
+        *self.0.lock().unwrap() = Some(session.clone());
+        Ok(())
+    }
+}
+
+/// Keeps the session in a file
+///
+/// The token in the file lets anyone who can read it act on behalf of the user until the
+/// token expires. On Unix, the file is only readable by the user that created it.
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg_attr(docsrs, doc(cfg(not(target_arch = "wasm32"))))]
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStore {
+    /// Keeps the session in the file at `path`, which is created when the session is saved
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SessionStore for FileStore {
+    fn load(&self) -> Result<Option<SavedSession>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_pack::from_slice(&bytes)?)),
+            Err(error) if error.kind() == IoErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn save(&self, session: &SavedSession) -> Result<()> {
+        let bytes = serde_pack::to_vec(session)?;
+        // write to a temporary file first so that a crash can't leave a partial session behind
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&temp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use crate::events::Events;
+use crate::param::from_value;
+use crate::param::DbResponse;
+use crate::param::Param;
+use crate::param::ServerAddrs;
+use crate::session::Session;
+use crate::Connection;
+use crate::ErrorKind;
+use crate::ExtractRouter;
+use crate::Method;
+use crate::Requests;
+use crate::Response as QueryResponse;
+use crate::Result;
+use crate::Route;
+use crate::Router;
+use crate::Surreal;
+use flume::Receiver;
+use flume::Sender;
+use futures::future;
+use futures::future::Either;
+use futures::StreamExt;
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+#[cfg(feature = "ws")]
+use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
+
+type AnyRoute = Route<(Method, Param, Receiver<()>), Result<DbResponse>>;
+
+/// A client that picks the protocol to use from the scheme of the endpoint it connects to
+#[derive(Debug, Clone)]
+pub struct Client {
+    method: Method,
+    // dropped along with the request, which tells the router to stop waiting for its response
+    alive: Option<Sender<()>>,
+}
+
+impl Connection for Client {
+    type Request = (Method, Param, Receiver<()>);
+    type Response = Result<DbResponse>;
+
+    fn new(method: Method) -> Self {
+        Self {
+            method,
+            alive: None,
+        }
+    }
+
+    fn connect(
+        address: ServerAddrs,
+        capacity: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
+        Box::pin(async move {
+            let (route_tx, route_rx) = match capacity {
+                0 => flume::unbounded(),
+                capacity => flume::bounded(capacity),
+            };
+            let (shutdown_tx, shutdown_rx) = flume::bounded(1);
+
+            let request_timeout = address.config.request_timeout;
+            let scheme = address.endpoint.scheme().to_owned();
+            let events = match scheme.as_str() {
+                #[cfg(feature = "ws")]
+                "ws" | "wss" => {
+                    let inner = crate::net::WsClient::connect(address, capacity).await?;
+                    router(inner, route_rx, shutdown_tx)?
+                }
+                #[cfg(feature = "http")]
+                "http" | "https" => {
+                    let inner = crate::net::HttpClient::connect(address, capacity).await?;
+                    router(inner, route_rx, shutdown_tx)?
+                }
+                #[cfg(feature = "mem")]
+                "mem" => {
+                    let inner = crate::net::DbClient::connect(address, capacity).await?;
+                    router(inner, route_rx, shutdown_tx)?
+                }
+                #[cfg(feature = "rocksdb")]
+                "file" => {
+                    let inner = crate::net::DbClient::connect(address, capacity).await?;
+                    router(inner, route_rx, shutdown_tx)?
+                }
+                scheme => {
+                    return Err(ErrorKind::InvalidParams.with_message(format!(
+                        "unsupported scheme `{scheme}`; make sure the feature for it is enabled"
+                    )));
+                }
+            };
+
+            Ok(Surreal {
+                router: OnceCell::with_value(Arc::new(Router {
+                    conn: PhantomData,
+                    sender: route_tx,
+                    timeout: request_timeout,
+                    events,
+                    requests: Requests::new(),
+                    session: Session::new(),
+                    owner: None,
+                    shutdown: Some(shutdown_rx),
+                    #[cfg(feature = "ws")]
+                    last_id: AtomicI64::new(0),
+                    #[cfg(feature = "ws")]
+                    cancelled: None,
+                })),
+            })
+        })
+    }
+
+    fn send<'r>(
+        &'r mut self,
+        router: &'r Router<Self>,
+        param: Param,
+    ) -> Pin<Box<dyn Future<Output = Result<Receiver<Self::Response>>> + Send + Sync + 'r>> {
+        Box::pin(async move {
+            let (sender, receiver) = flume::bounded(1);
+            let (alive_tx, alive_rx) = flume::bounded(1);
+            self.alive = Some(alive_tx);
+            let route = Route {
+                request: (self.method, param, alive_rx),
+                response: sender,
+            };
+            router.sender.send_async(Some(route)).await?;
+            Ok(receiver)
+        })
+    }
+
+    fn recv<R>(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<R>> + Send + Sync + '_>>
+    where
+        R: DeserializeOwned,
+    {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Other(value) => from_value(&value),
+                DbResponse::Query(..) => unreachable!(),
+            }
+        })
+    }
+
+    fn recv_query(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<QueryResponse>> + Send + Sync + '_>> {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Query(response) => Ok(response),
+                DbResponse::Other(..) => unreachable!(),
+            }
+        })
+    }
+}
+
+/// Forwards requests to the client of the protocol that was picked
+///
+/// Returns the connection events of that client so they can be shared.
+fn router<C>(
+    inner: Surreal<C>,
+    route_rx: Receiver<Option<AnyRoute>>,
+    shutdown_tx: Sender<Result<()>>,
+) -> Result<Arc<Events>>
+where
+    C: Connection<Response = Result<DbResponse>>,
+{
+    let events = inner.router.extract()?.events.clone();
+    spawn(async move {
+        let mut stream = route_rx.into_stream();
+
+        while let Some(Some(route)) = stream.next().await {
+            let (method, param, alive) = route.request;
+            // requests are handed over in order but their responses are awaited concurrently
+            match send(&inner, method, param).await {
+                // the request is cancelled when the connection drops it, so it's kept until
+                // either the response is in or the caller stops waiting for it
+                Ok((conn, rx)) => spawn(async move {
+                    match future::select(rx.into_recv_async(), alive.into_recv_async()).await {
+                        Either::Left((response, _)) => {
+                            let response = match response {
+                                Ok(response) => response,
+                                Err(error) => Err(error.into()),
+                            };
+                            let _ = route.response.into_send_async(response).await;
+                        }
+                        Either::Right(..) => {
+                            tracing::trace!("Request dropped before it got a response");
+                        }
+                    }
+                    drop(conn);
+                }),
+                Err(error) => {
+                    let _ = route.response.into_send_async(Err(error)).await;
+                }
+            }
+        }
+        let result = inner.close().await;
+        if let Err(error) = &result {
+            tracing::trace!("Failed to close the connection; {error}");
+        }
+        let _res = shutdown_tx.send(result);
+    });
+    Ok(events)
+}
+
+/// Hands a request over to the client of the protocol that was picked
+///
+/// Returns the connection that sent it along with the receiver of its response.
+async fn send<C>(
+    inner: &Surreal<C>,
+    method: Method,
+    param: Param,
+) -> Result<(C, Receiver<Result<DbResponse>>)>
+where
+    C: Connection<Response = Result<DbResponse>>,
+{
+    let router = inner.router.extract()?;
+    let mut conn = C::new(method);
+    let rx = conn.send(router, param).await?;
+    Ok((conn, rx))
+}
+
+fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::spawn(future);
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(future);
+}
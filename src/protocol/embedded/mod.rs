@@ -0,0 +1,446 @@
+use crate::events::Events;
+use crate::param::from_value;
+use crate::param::DbResponse;
+use crate::param::Param;
+use crate::param::ServerAddrs;
+use crate::protocol::statement::create_statement;
+use crate::protocol::statement::delete_statement;
+use crate::protocol::statement::merge_statement;
+use crate::protocol::statement::patch_statement;
+use crate::protocol::statement::select_statement;
+use crate::protocol::statement::update_statement;
+use crate::session::Session;
+use crate::Connection;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use crate::Error;
+use crate::ErrorKind;
+use crate::Method;
+use crate::Requests;
+use crate::Response as QueryResponse;
+use crate::Result;
+use crate::Route;
+use crate::Router;
+use crate::Stats;
+use crate::Status;
+use crate::Surreal;
+use flume::Receiver;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use futures::future;
+use futures::StreamExt;
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use std::path::PathBuf;
+use std::pin::Pin;
+#[cfg(feature = "ws")]
+use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
+use surrealdb::sql::Array;
+use surrealdb::sql::Object;
+use surrealdb::sql::Strand;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Session;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use tokio::fs;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use tokio::fs::OpenOptions;
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+use tokio::io::AsyncWriteExt;
+
+type DbRoute = Route<(Method, Param), Result<DbResponse>>;
+
+/// An embedded database client that runs queries in-process
+#[derive(Debug, Clone)]
+pub struct Client {
+    method: Method,
+}
+
+impl Connection for Client {
+    type Request = (Method, Param);
+    type Response = Result<DbResponse>;
+
+    fn new(method: Method) -> Self {
+        Self { method }
+    }
+
+    fn connect(
+        address: ServerAddrs,
+        capacity: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
+        Box::pin(async move {
+            let path = match address.endpoint.scheme() {
+                #[cfg(feature = "mem")]
+                "mem" => "memory".to_owned(),
+                #[cfg(feature = "rocksdb")]
+                "file" => match address.endpoint.to_file_path() {
+                    Ok(path) => format!("file://{}", path.display()),
+                    Err(()) => {
+                        return Err(ErrorKind::InvalidParams
+                            .with_message(format!("`{}` is not a file path", address.endpoint)));
+                    }
+                },
+                scheme => {
+                    return Err(ErrorKind::InvalidParams.with_message(format!(
+                        "unsupported embedded scheme `{scheme}`; make sure the feature for it is enabled"
+                    )));
+                }
+            };
+
+            let (route_tx, route_rx) = match capacity {
+                0 => flume::unbounded(),
+                capacity => flume::bounded(capacity),
+            };
+
+            let (conn_tx, conn_rx) = flume::bounded(1);
+
+            router(path, conn_tx, route_rx);
+
+            conn_rx.into_recv_async().await??;
+
+            Ok(Surreal {
+                router: OnceCell::with_value(Arc::new(Router {
+                    conn: PhantomData,
+                    sender: route_tx,
+                    timeout: address.config.request_timeout,
+                    events: Events::connected(),
+                    requests: Requests::new(),
+                    session: Session::new(),
+                    owner: None,
+                    shutdown: None,
+                    #[cfg(feature = "ws")]
+                    last_id: AtomicI64::new(0),
+                    #[cfg(feature = "ws")]
+                    cancelled: None,
+                })),
+            })
+        })
+    }
+
+    fn send<'r>(
+        &'r mut self,
+        router: &'r Router<Self>,
+        param: Param,
+    ) -> Pin<Box<dyn Future<Output = Result<Receiver<Self::Response>>> + Send + Sync + 'r>> {
+        Box::pin(async move {
+            let (sender, receiver) = flume::bounded(1);
+            let route = Route {
+                request: (self.method, param),
+                response: sender,
+            };
+            router.sender.send_async(Some(route)).await?;
+            Ok(receiver)
+        })
+    }
+
+    fn recv<R>(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<R>> + Send + Sync + '_>>
+    where
+        R: DeserializeOwned,
+    {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Other(value) => from_value(&value),
+                DbResponse::Query(..) => unreachable!(),
+            }
+        })
+    }
+
+    fn recv_query(
+        &mut self,
+        rx: Receiver<Self::Response>,
+    ) -> Pin<Box<dyn Future<Output = Result<QueryResponse>> + Send + Sync + '_>> {
+        Box::pin(async move {
+            let response = rx.into_recv_async().await?;
+            tracing::trace!("Response {response:?}");
+            match response? {
+                DbResponse::Query(response) => Ok(response),
+                DbResponse::Other(..) => unreachable!(),
+            }
+        })
+    }
+}
+
+fn router(path: String, conn_tx: flume::Sender<Result<()>>, route_rx: Receiver<Option<DbRoute>>) {
+    let future = async move {
+        let kvs = match Datastore::new(&path).await {
+            Ok(kvs) => {
+                let _ = conn_tx.into_send_async(Ok(())).await;
+                kvs
+            }
+            Err(error) => {
+                let _ = conn_tx.into_send_async(Err(error.into())).await;
+                return;
+            }
+        };
+
+        let mut session = Session::for_kv();
+        let mut vars = BTreeMap::new();
+        let mut stream = route_rx.into_stream();
+
+        while let Some(Some(route)) = stream.next().await {
+            let response = execute(route.request, &kvs, &mut session, &mut vars).await;
+            let _ = route.response.into_send_async(response).await;
+        }
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::spawn(future);
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+async fn query(
+    kvs: &Datastore,
+    session: &Session,
+    vars: &BTreeMap<String, Value>,
+    statements: &str,
+) -> Result<Vec<surrealdb::Response>> {
+    let responses = kvs
+        .execute(statements, session, Some(vars.clone()), false)
+        .await?;
+    Ok(responses)
+}
+
+fn take(one: bool, responses: Vec<surrealdb::Response>) -> Result<Value> {
+    if let Some(response) = responses.into_iter().next() {
+        match response.result? {
+            Value::Array(Array(mut vec)) => {
+                if one {
+                    if let [value] = &mut vec[..] {
+                        return Ok(mem::take(value));
+                    }
+                } else {
+                    return Ok(Value::Array(Array(vec)));
+                }
+            }
+            Value::None | Value::Null => {}
+            value => {
+                return Ok(value);
+            }
+        }
+    }
+    match one {
+        true => Ok(Value::None),
+        false => Ok(Value::Array(Array(vec![]))),
+    }
+}
+
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+async fn export(kvs: &Datastore, session: &Session, file: PathBuf) -> Result<()> {
+    let (ns, db) = match (&session.ns, &session.db) {
+        (Some(ns), Some(db)) => (ns.clone(), db.clone()),
+        _ => {
+            return Err(ErrorKind::InvalidRequest
+                .with_message("select a namespace and database before exporting"));
+        }
+    };
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(file)
+        .await?;
+    let (sender, receiver) = surrealdb::channel::new(1);
+    let write = async {
+        while let Ok(bytes) = receiver.recv().await {
+            file.write_all(&bytes).await?;
+        }
+        file.flush().await?;
+        Ok::<_, Error>(())
+    };
+    let (exported, written) = future::join(kvs.export(ns, db, sender), write).await;
+    exported?;
+    written
+}
+
+#[cfg(all(
+    any(feature = "http", feature = "rocksdb"),
+    not(target_arch = "wasm32")
+))]
+async fn import(
+    kvs: &Datastore,
+    session: &Session,
+    vars: &BTreeMap<String, Value>,
+    file: PathBuf,
+) -> Result<()> {
+    let statements = fs::read_to_string(file).await?;
+    for response in query(kvs, session, vars, &statements).await? {
+        response.result?;
+    }
+    Ok(())
+}
+
+fn query_response(responses: Vec<surrealdb::Response>) -> QueryResponse {
+    let mut results = Vec::with_capacity(responses.len());
+    let mut stats = Vec::with_capacity(responses.len());
+    for response in responses {
+        let status = match response.result {
+            Ok(..) => Status::Ok,
+            Err(..) => Status::Err,
+        };
+        stats.push(Stats {
+            execution_time: Some(response.time),
+            status,
+        });
+        let result = match response.result {
+            Ok(Value::Array(Array(values))) => Ok(values),
+            Ok(Value::None | Value::Null) => Ok(Vec::new()),
+            Ok(value) => Ok(vec![value]),
+            Err(error) => {
+                let message = error.to_string();
+                Err(ErrorKind::from_query_error(&message).with_context(message))
+            }
+        };
+        results.push(result);
+    }
+    QueryResponse { results, stats }
+}
+
+async fn execute(
+    (method, param): (Method, Param),
+    kvs: &Datastore,
+    session: &mut Session,
+    vars: &mut BTreeMap<String, Value>,
+) -> Result<DbResponse> {
+    let mut params = param.query;
+
+    match method {
+        Method::Use => {
+            let (ns, db) = match &mut params[..] {
+                [Value::Strand(Strand(ns)), Value::Strand(Strand(db))] => {
+                    (mem::take(ns), mem::take(db))
+                }
+                _ => unreachable!(),
+            };
+            session.ns = Some(ns);
+            session.db = Some(db);
+            Ok(DbResponse::Other(Value::None))
+        }
+        Method::Signin | Method::Signup | Method::Authenticate => {
+            Err(ErrorKind::MethodNotSupported.with_message(format!(
+                "`{}` is not supported by embedded databases",
+                method.as_str()
+            )))
+        }
+        Method::Invalidate => Ok(DbResponse::Other(Value::None)),
+        Method::Create => {
+            let statement = create_statement(&mut params);
+            let responses = query(kvs, session, vars, &statement.to_string()).await?;
+            let value = take(true, responses)?;
+            Ok(DbResponse::Other(value))
+        }
+        Method::Update => {
+            let (one, statement) = update_statement(&mut params);
+            let responses = query(kvs, session, vars, &statement.to_string()).await?;
+            let value = take(one, responses)?;
+            Ok(DbResponse::Other(value))
+        }
+        Method::Patch => {
+            let (one, statement) = patch_statement(&mut params);
+            let responses = query(kvs, session, vars, &statement.to_string()).await?;
+            let value = take(one, responses)?;
+            Ok(DbResponse::Other(value))
+        }
+        Method::Merge => {
+            let (one, statement) = merge_statement(&mut params);
+            let responses = query(kvs, session, vars, &statement.to_string()).await?;
+            let value = take(one, responses)?;
+            Ok(DbResponse::Other(value))
+        }
+        Method::Select => {
+            let (one, statement) = select_statement(&mut params);
+            let responses = query(kvs, session, vars, &statement.to_string()).await?;
+            let value = take(one, responses)?;
+            Ok(DbResponse::Other(value))
+        }
+        Method::Delete => {
+            let statement = delete_statement(&mut params);
+            let responses = query(kvs, session, vars, &statement.to_string()).await?;
+            let value = take(true, responses)?;
+            Ok(DbResponse::Other(value))
+        }
+        Method::Query => {
+            let responses = match &mut params[..] {
+                [Value::Strand(Strand(statements))] => {
+                    query(kvs, session, vars, statements).await?
+                }
+                [Value::Strand(Strand(statements)), Value::Object(Object(bindings))] => {
+                    let mut vars = vars.clone();
+                    vars.append(bindings);
+                    query(kvs, session, &vars, statements).await?
+                }
+                _ => unreachable!(),
+            };
+            Ok(DbResponse::Query(query_response(responses)))
+        }
+        #[cfg(all(
+            any(feature = "http", feature = "rocksdb"),
+            not(target_arch = "wasm32")
+        ))]
+        Method::Export => {
+            let file = param.file.expect("file to export into");
+            export(kvs, session, file).await?;
+            Ok(DbResponse::Other(Value::None))
+        }
+        #[cfg(all(
+            any(feature = "http", feature = "rocksdb"),
+            not(target_arch = "wasm32")
+        ))]
+        Method::Import => {
+            let file = param.file.expect("file to import from");
+            import(kvs, session, vars, file).await?;
+            Ok(DbResponse::Other(Value::None))
+        }
+        Method::Health => Ok(DbResponse::Other(Value::None)),
+        Method::Version => Err(ErrorKind::MethodNotSupported.with_message(
+            "embedded databases are compiled into the client, they have no server version",
+        )),
+        Method::Set => {
+            let (key, value) = match &mut params[..2] {
+                [Value::Strand(Strand(key)), value] => (mem::take(key), mem::take(value)),
+                _ => unreachable!(),
+            };
+            vars.insert(key, value);
+            Ok(DbResponse::Other(Value::None))
+        }
+        Method::Unset => {
+            if let [Value::Strand(Strand(key))] = &params[..1] {
+                vars.remove(key);
+            }
+            Ok(DbResponse::Other(Value::None))
+        }
+        Method::Live | Method::Kill => Err(ErrorKind::LiveQueriesNotSupported
+            .with_message("live queries are only supported over WebSockets")),
+    }
+}
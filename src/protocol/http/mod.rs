@@ -7,11 +7,20 @@ use crate::param::from_json;
 use crate::param::from_value;
 use crate::param::DbResponse;
 use crate::param::Param;
-use crate::protocol::Status;
+use crate::protocol::parse_duration;
+use crate::protocol::statement::create_statement;
+use crate::protocol::statement::delete_statement;
+use crate::protocol::statement::merge_statement;
+use crate::protocol::statement::patch_statement;
+use crate::protocol::statement::select_statement;
+use crate::protocol::statement::update_statement;
 use crate::ErrorKind;
 use crate::Method;
+use crate::Response as QueryResponse;
 use crate::Result;
 use crate::Route;
+use crate::Stats;
+use crate::Status;
 #[cfg(not(target_arch = "wasm32"))]
 use futures::TryStreamExt;
 use indexmap::IndexMap;
@@ -27,18 +36,9 @@ use serde::Serialize;
 use std::mem;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
-use surrealdb::sql::statements::CreateStatement;
-use surrealdb::sql::statements::DeleteStatement;
-use surrealdb::sql::statements::SelectStatement;
-use surrealdb::sql::statements::UpdateStatement;
 use surrealdb::sql::Array;
-use surrealdb::sql::Data;
-use surrealdb::sql::Field;
-use surrealdb::sql::Fields;
-use surrealdb::sql::Output;
 use surrealdb::sql::Strand;
 use surrealdb::sql::Value;
-use surrealdb::sql::Values;
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::fs::OpenOptions;
 #[cfg(not(target_arch = "wasm32"))]
@@ -79,7 +79,8 @@ impl Authenticate for RequestBuilder {
 }
 
 #[derive(Debug, Deserialize)]
-struct QueryResponse {
+struct StatementResponse {
+    time: Option<String>,
     status: Status,
     result: Option<serde_json::Value>,
     detail: Option<String>,
@@ -104,32 +105,33 @@ async fn submit_auth(request: RequestBuilder) -> Result<Value> {
     Ok(response.token.filter(|token| token != "NONE").into())
 }
 
-async fn query(request: RequestBuilder) -> Result<Vec<Result<Vec<Value>>>> {
+async fn query(request: RequestBuilder) -> Result<QueryResponse> {
     tracing::info!("{request:?}");
     let response = request.send().await?.error_for_status()?;
     let text = response.text().await?;
     tracing::info!("Response {text}");
-    let responses: Vec<QueryResponse> = serde_json::from_str(&text)?;
-    let mut vec = Vec::with_capacity(responses.len());
+    let responses: Vec<StatementResponse> = serde_json::from_str(&text)?;
+    let mut results = Vec::with_capacity(responses.len());
+    let mut stats = Vec::with_capacity(responses.len());
     for response in responses {
+        stats.push(Stats {
+            execution_time: response.time.as_deref().and_then(parse_duration),
+            status: response.status,
+        });
         match response.status {
-            Status::Ok => {
-                if let Some(value) = response.result {
-                    match from_json(value) {
-                        Value::Array(Array(array)) => vec.push(Ok(array)),
-                        Value::None | Value::Null => vec.push(Ok(vec![])),
-                        value => vec.push(Ok(vec![value])),
-                    }
-                }
-            }
+            Status::Ok => match response.result.map(from_json) {
+                Some(Value::Array(Array(array))) => results.push(Ok(array)),
+                Some(Value::None | Value::Null) | None => results.push(Ok(vec![])),
+                Some(value) => results.push(Ok(vec![value])),
+            },
             Status::Err => {
-                if let Some(error) = response.detail {
-                    vec.push(Err(ErrorKind::Query.with_context(error)));
-                }
+                let error = response.detail.unwrap_or_default();
+                let kind = ErrorKind::from_query_error(&error);
+                results.push(Err(kind.with_context(error)));
             }
         }
     }
-    Ok(vec)
+    Ok(QueryResponse { results, stats })
 }
 
 async fn take(one: bool, request: RequestBuilder) -> Result<Value> {
@@ -200,106 +202,6 @@ async fn health(request: RequestBuilder) -> Result<Value> {
     Ok(Value::None)
 }
 
-fn split_params(params: &mut [Value]) -> (bool, Values, Value) {
-    let (what, data) = match params {
-        [what] => (mem::take(what), Value::None),
-        [what, data] => (mem::take(what), mem::take(data)),
-        _ => unreachable!(),
-    };
-    let one = what.is_thing();
-    let what = match what {
-        Value::Array(Array(vec)) => Values(vec),
-        value => Values(vec![value]),
-    };
-    (one, what, data)
-}
-
-fn create_statement(params: &mut [Value]) -> CreateStatement {
-    let (_, what, data) = split_params(params);
-    let data = match data {
-        Value::None => None,
-        value => Some(Data::ContentExpression(value)),
-    };
-    CreateStatement {
-        what,
-        data,
-        output: Some(Output::After),
-        ..Default::default()
-    }
-}
-
-fn update_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
-    let (one, what, data) = split_params(params);
-    let data = match data {
-        Value::None => None,
-        value => Some(Data::ContentExpression(value)),
-    };
-    (
-        one,
-        UpdateStatement {
-            what,
-            data,
-            output: Some(Output::After),
-            ..Default::default()
-        },
-    )
-}
-
-fn patch_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
-    let (one, what, data) = split_params(params);
-    let data = match data {
-        Value::None => None,
-        value => Some(Data::PatchExpression(value)),
-    };
-    (
-        one,
-        UpdateStatement {
-            what,
-            data,
-            output: Some(Output::Diff),
-            ..Default::default()
-        },
-    )
-}
-
-fn merge_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
-    let (one, what, data) = split_params(params);
-    let data = match data {
-        Value::None => None,
-        value => Some(Data::MergeExpression(value)),
-    };
-    (
-        one,
-        UpdateStatement {
-            what,
-            data,
-            output: Some(Output::After),
-            ..Default::default()
-        },
-    )
-}
-
-fn select_statement(params: &mut [Value]) -> (bool, SelectStatement) {
-    let (one, what, _) = split_params(params);
-    (
-        one,
-        SelectStatement {
-            what,
-            expr: Fields(vec![Field::All]),
-            ..Default::default()
-        },
-    )
-}
-
-fn delete_statement(params: &mut [Value]) -> DeleteStatement {
-    let (_, what, _) = split_params(params);
-    DeleteStatement {
-        what,
-        output: Some(Output::None),
-        ..Default::default()
-    }
-}
-
 async fn router(
     (method, param): (Method, Param),
     base_url: &Url,
@@ -473,8 +375,8 @@ async fn router(
                 }
                 _ => unreachable!(),
             }
-            let values = query(request).await?;
-            Ok(DbResponse::Query(values))
+            let response = query(request).await?;
+            Ok(DbResponse::Query(response))
         }
         #[cfg(not(target_arch = "wasm32"))]
         Method::Export => {
@@ -534,21 +436,8 @@ async fn router(
             }
             Ok(DbResponse::Other(Value::None))
         }
-        Method::Live => {
-            let path = base_url.join(SQL_PATH)?;
-            let table = match &params[..] {
-                [table] => table.to_string(),
-                _ => unreachable!(),
-            };
-            let request = client
-                .post(path)
-                .headers(headers.clone())
-                .auth(&auth)
-                .query(&[("table", table)])
-                .body("LIVE SELECT * FROM type::table($table)");
-            let value = take(true, request).await?;
-            Ok(DbResponse::Other(value))
-        }
+        Method::Live => Err(ErrorKind::LiveQueriesNotSupported
+            .with_message("live queries are only supported over WebSockets")),
         Method::Kill => {
             let path = base_url.join(SQL_PATH)?;
             let id = match &params[..] {
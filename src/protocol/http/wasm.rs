@@ -1,11 +1,14 @@
 use super::Client;
 use super::HttpRoute;
+use crate::events::Events;
 use crate::param::from_value;
 use crate::param::DbResponse;
 use crate::param::Param;
 use crate::param::ServerAddrs;
+use crate::session::Session;
 use crate::Connection;
 use crate::Method;
+use crate::Requests;
 use crate::Response as QueryResponse;
 use crate::Result;
 use crate::Route;
@@ -50,6 +53,7 @@ impl Connection for Client {
 
             let (conn_tx, conn_rx) = flume::bounded(1);
 
+            let request_timeout = address.config.request_timeout;
             router(address, conn_tx, route_rx);
 
             if let Err(error) = conn_rx.into_recv_async().await? {
@@ -60,8 +64,16 @@ impl Connection for Client {
                 router: OnceCell::with_value(Arc::new(Router {
                     conn: PhantomData,
                     sender: route_tx,
+                    timeout: request_timeout,
+                    events: Events::connected(),
+                    requests: Requests::new(),
+                    session: Session::new(),
+                    owner: None,
+                    shutdown: None,
                     #[cfg(feature = "ws")]
                     last_id: AtomicI64::new(0),
+                    #[cfg(feature = "ws")]
+                    cancelled: None,
                 })),
             })
         })
@@ -109,7 +121,7 @@ impl Connection for Client {
             let response = rx.into_recv_async().await?;
             tracing::trace!("Response {response:?}");
             match response? {
-                DbResponse::Query(results) => Ok(results),
+                DbResponse::Query(response) => Ok(response),
                 DbResponse::Other(..) => unreachable!(),
             }
         })
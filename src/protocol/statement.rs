@@ -0,0 +1,115 @@
+//! Statements shared by the protocols that send SurrealQL instead of RPC calls, and by
+//! logical sessions
+
+use std::mem;
+use surrealdb::sql::statements::CreateStatement;
+use surrealdb::sql::statements::DeleteStatement;
+use surrealdb::sql::statements::SelectStatement;
+use surrealdb::sql::statements::UpdateStatement;
+use surrealdb::sql::Array;
+use surrealdb::sql::Data;
+use surrealdb::sql::Field;
+use surrealdb::sql::Fields;
+use surrealdb::sql::Output;
+use surrealdb::sql::Value;
+use surrealdb::sql::Values;
+
+fn split_params(params: &mut [Value]) -> (bool, Values, Value) {
+    let (what, data) = match params {
+        [what] => (mem::take(what), Value::None),
+        [what, data] => (mem::take(what), mem::take(data)),
+        _ => unreachable!(),
+    };
+    let one = what.is_thing();
+    let what = match what {
+        Value::Array(Array(vec)) => Values(vec),
+        value => Values(vec![value]),
+    };
+    (one, what, data)
+}
+
+pub(crate) fn create_statement(params: &mut [Value]) -> CreateStatement {
+    let (_, what, data) = split_params(params);
+    let data = match data {
+        Value::None => None,
+        value => Some(Data::ContentExpression(value)),
+    };
+    CreateStatement {
+        what,
+        data,
+        output: Some(Output::After),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn update_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
+    let (one, what, data) = split_params(params);
+    let data = match data {
+        Value::None => None,
+        value => Some(Data::ContentExpression(value)),
+    };
+    (
+        one,
+        UpdateStatement {
+            what,
+            data,
+            output: Some(Output::After),
+            ..Default::default()
+        },
+    )
+}
+
+pub(crate) fn patch_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
+    let (one, what, data) = split_params(params);
+    let data = match data {
+        Value::None => None,
+        value => Some(Data::PatchExpression(value)),
+    };
+    (
+        one,
+        UpdateStatement {
+            what,
+            data,
+            output: Some(Output::Diff),
+            ..Default::default()
+        },
+    )
+}
+
+pub(crate) fn merge_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
+    let (one, what, data) = split_params(params);
+    let data = match data {
+        Value::None => None,
+        value => Some(Data::MergeExpression(value)),
+    };
+    (
+        one,
+        UpdateStatement {
+            what,
+            data,
+            output: Some(Output::After),
+            ..Default::default()
+        },
+    )
+}
+
+pub(crate) fn select_statement(params: &mut [Value]) -> (bool, SelectStatement) {
+    let (one, what, _) = split_params(params);
+    (
+        one,
+        SelectStatement {
+            what,
+            expr: Fields(vec![Field::All]),
+            ..Default::default()
+        },
+    )
+}
+
+pub(crate) fn delete_statement(params: &mut [Value]) -> DeleteStatement {
+    let (_, what, _) = split_params(params);
+    DeleteStatement {
+        what,
+        output: Some(Output::None),
+        ..Default::default()
+    }
+}
@@ -4,29 +4,138 @@ mod native;
 mod wasm;
 
 use crate::method::Method;
+use crate::param::from_value;
 use crate::param::DbResponse;
+use crate::param::Notification;
 use crate::param::Param;
-use crate::protocol::Status;
+use crate::protocol::parse_duration;
+use crate::Error;
 use crate::ErrorKind;
+use crate::Response as QueryResponse;
 use crate::Result;
 use crate::Route;
+use crate::Stats;
+use crate::Status;
+use flume::Sender;
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::mem;
 use std::time::Duration;
 use surrealdb::sql::Array;
+use surrealdb::sql::Object;
+use surrealdb::sql::Uuid;
 use surrealdb::sql::Value;
 
 type WsRoute = Route<(i64, Method, Param), Result<DbResponse>>;
 
 const PATH: &str = "rpc";
-const PING_INTERVAL: Duration = Duration::from_secs(5);
+/// The shortest ping interval the router accepts, as `tokio` can't tick at an interval of zero
+const MIN_PING_INTERVAL: Duration = Duration::from_millis(1);
 const PING_METHOD: &str = "ping";
 
+/// A live query registered on the server
+#[derive(Debug, Clone)]
+struct LiveQuery {
+    /// The ID the caller knows this live query by
+    ///
+    /// This stays the same even when the live query is re-registered after a reconnect.
+    query_id: Uuid,
+    /// The parameters used to start the live query
+    params: Vec<Value>,
+    sender: Sender<Result<Notification<Value>>>,
+}
+
+/// Live queries registered on the server, keyed by the ID the server knows them by
+#[derive(Debug, Default)]
+struct LiveQueries(HashMap<Uuid, LiveQuery>);
+
+impl LiveQueries {
+    /// Tracks a live query once the server confirms that it was started
+    fn insert(
+        &mut self,
+        (params, sender): (Vec<Value>, Sender<Result<Notification<Value>>>),
+        response: &Result<DbResponse>,
+    ) {
+        if let Ok(DbResponse::Other(value)) = response {
+            if let Ok(query_id) = from_value::<Uuid>(value) {
+                let live_query = LiveQuery {
+                    query_id: query_id.clone(),
+                    params,
+                    sender,
+                };
+                self.0.insert(query_id, live_query);
+            }
+        }
+    }
+
+    /// Stops tracking a live query that is about to be killed
+    ///
+    /// The ID in the kill request is rewritten to the one the server currently knows the
+    /// live query by, in case it was registered again after a reconnect.
+    fn kill(&mut self, params: &mut [Value]) {
+        if let [query_id] = params {
+            if let Ok(id) = from_value::<Uuid>(query_id) {
+                let server_id = self
+                    .0
+                    .iter()
+                    .find(|(_, live_query)| live_query.query_id == id)
+                    .map(|(server_id, _)| server_id.clone());
+                if let Some(server_id) = server_id {
+                    self.0.remove(&server_id);
+                    *query_id = server_id.into();
+                }
+            }
+        }
+    }
+
+    /// Stops tracking all live queries, returning the requests that kill them on the server
+    fn kill_all(&mut self) -> Vec<Value> {
+        self.0
+            .drain()
+            .map(|(server_id, _)| {
+                let mut request = BTreeMap::new();
+                request.insert("method".to_owned(), Method::Kill.as_str().into());
+                request.insert("params".to_owned(), vec![Value::from(server_id)].into());
+                Value::from(request)
+            })
+            .collect()
+    }
+
+    /// Forwards a notification to the stream of the live query that produced it
+    async fn notify(&mut self, mut notification: Notification<Value>) {
+        if let Some(live_query) = self.0.get(&notification.query_id) {
+            notification.query_id = live_query.query_id.clone();
+            if live_query
+                .sender
+                .send_async(Ok(notification))
+                .await
+                .is_err()
+            {
+                tracing::trace!("Live query stream dropped");
+                self.0
+                    .retain(|_, live_query| !live_query.sender.is_disconnected());
+            }
+        }
+    }
+}
+
 /// A WebSocket client for communicating with the server via WebSockets
 #[derive(Debug, Clone)]
 pub struct Client {
     id: i64,
     method: Method,
+    /// Set while the request is waiting for a response
+    cancelled: Option<Sender<i64>>,
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // the caller dropped the request before it got a response
+        if let Some(cancelled) = self.cancelled.take() {
+            let _res = cancelled.send(self.id);
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -60,10 +169,17 @@ pub(crate) enum Content {
 impl DbResponse {
     fn from((method, content): (Method, Content)) -> Result<Self> {
         match content {
-            Content::Success(SuccessValue::Query(results)) => Ok(DbResponse::Query(
-                results
+            Content::Success(SuccessValue::Query(results)) => {
+                let stats = results
+                    .iter()
+                    .map(|(time, status, _)| Stats {
+                        execution_time: parse_duration(time),
+                        status: *status,
+                    })
+                    .collect();
+                let results = results
                     .into_iter()
-                    .map(|(_duration, status, result)| match status {
+                    .map(|(_time, status, result)| match status {
                         Status::Ok => match result {
                             QueryMethodResponse::Value(value) => match value {
                                 Value::Array(Array(values)) => Ok(values),
@@ -74,15 +190,17 @@ impl DbResponse {
                         },
                         Status::Err => match result {
                             QueryMethodResponse::Value(message) => {
-                                Err(ErrorKind::Query.with_message(message.to_string()))
+                                let message = message.to_string();
+                                Err(ErrorKind::from_query_error(&message).with_message(message))
                             }
                             QueryMethodResponse::String(message) => {
-                                Err(ErrorKind::Query.with_message(message))
+                                Err(ErrorKind::from_query_error(&message).with_message(message))
                             }
                         },
                     })
-                    .collect(),
-            )),
+                    .collect();
+                Ok(DbResponse::Query(QueryResponse { results, stats }))
+            }
             Content::Success(SuccessValue::Other(mut value)) => {
                 if let Method::Create | Method::Delete = method {
                     if let Value::Array(Array(array)) = &mut value {
@@ -104,6 +222,57 @@ impl DbResponse {
     }
 }
 
+impl Content {
+    /// Extracts a live query notification pushed by the server
+    fn into_notification(self) -> Option<Notification<Value>> {
+        match self {
+            Content::Success(SuccessValue::Other(Value::Object(Object(mut object)))) => {
+                let query_id = from_value(&object.remove("id")?).ok()?;
+                let action = from_value(&object.remove("action")?).ok()?;
+                let data = object.remove("result").unwrap_or_default();
+                Some(Notification {
+                    query_id,
+                    action,
+                    data,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Reports what went wrong while shutting the connection down, if anything
+fn shutdown_result(errors: Vec<String>) -> Result<()> {
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(ErrorKind::Socket.with_message(errors.join("; "))),
+    }
+}
+
+fn connection_lost() -> Error {
+    ErrorKind::ConnectionLost
+        .with_message("the connection to the server dropped before it responded to the request")
+}
+
+/// Whether a request can safely be sent again after reconnecting
+const fn is_idempotent_read(method: Method) -> bool {
+    matches!(method, Method::Select | Method::Version | Method::Health)
+}
+
+/// Builds the request that starts a live query again on a new connection
+fn relive_request(id: i64, params: Vec<Value>) -> Value {
+    let mut request = BTreeMap::new();
+    request.insert("id".to_owned(), Value::from(id));
+    request.insert("method".to_owned(), Method::Live.as_str().into());
+    request.insert("params".to_owned(), params.into());
+    Value::from(request)
+}
+
+fn notifications_missed() -> Error {
+    ErrorKind::NotificationsMissed
+        .with_message("reconnected to the server; live query notifications may have been missed")
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,3 +280,20 @@ pub struct Response {
     #[serde(flatten)]
     pub(crate) content: Content,
 }
+
+impl Response {
+    /// The ID the server assigned to the live query started by the request with this ID, if
+    /// this is the response to it
+    fn relived(self, id: i64) -> Option<Result<Uuid>> {
+        if self.id.as_ref().map(Value::as_int) != Some(id) {
+            return None;
+        }
+        let result = match DbResponse::from((Method::Live, self.content)) {
+            Ok(DbResponse::Other(value)) => from_value(&value),
+            Ok(DbResponse::Query(..)) => Err(ErrorKind::InternalError
+                .with_message("the server answered a live query with a query response")),
+            Err(error) => Err(error),
+        };
+        Some(result)
+    }
+}
@@ -1,32 +1,43 @@
 use super::PATH;
+use crate::connection_closed;
+use crate::events::Events;
 use crate::param::from_value;
+use crate::param::Config;
 use crate::param::DbResponse;
 use crate::param::Param;
 use crate::param::ServerAddrs;
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 use crate::param::Tls;
+use crate::protocol::ws::connection_lost;
+use crate::protocol::ws::is_idempotent_read;
+use crate::protocol::ws::notifications_missed;
+use crate::protocol::ws::relive_request;
+use crate::protocol::ws::shutdown_result;
 use crate::protocol::ws::Client;
+use crate::protocol::ws::LiveQueries;
 use crate::protocol::ws::Response;
 use crate::protocol::ws::WsRoute;
-use crate::protocol::ws::PING_INTERVAL;
+use crate::protocol::ws::MIN_PING_INTERVAL;
 use crate::protocol::ws::PING_METHOD;
+use crate::session::Session;
 use crate::Connection;
+use crate::ConnectionEvent;
 use crate::ErrorKind;
 use crate::Method;
+use crate::Requests;
 use crate::Response as QueryResponse;
 use crate::Result;
 use crate::Route;
 use crate::Router;
 use crate::Surreal;
 use flume::Receiver;
-use futures::stream::SplitSink;
+use flume::Sender;
 use futures::SinkExt;
 use futures::StreamExt;
 use futures_concurrency::stream::Merge as _;
 use indexmap::IndexMap;
 use once_cell::sync::OnceCell;
 use serde::de::DeserializeOwned;
-use std::borrow::BorrowMut;
 use std::collections::hash_map::Entry;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -38,6 +49,7 @@ use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
 use std::time::Instant;
 use surrealdb::sql::Strand;
+use surrealdb::sql::Uuid;
 use surrealdb::sql::Value;
 use tokio::net::TcpStream;
 use tokio::time;
@@ -53,11 +65,9 @@ use url::Url;
 
 type WsResult<T> = std::result::Result<T, WsError>;
 
-const MAX_MESSAGE_SIZE: usize = 64 << 20; // 64 MiB
-const MAX_FRAME_SIZE: usize = 16 << 20; // 16 MiB
-
 pub(crate) enum Either<S, R> {
     Request(Option<Route<S, R>>),
+    Cancelled(i64),
     Response(WsResult<Message>),
     Ping,
 }
@@ -94,7 +104,11 @@ impl Connection for Client {
     type Response = Result<DbResponse>;
 
     fn new(method: Method) -> Self {
-        Self { id: 0, method }
+        Self {
+            id: 0,
+            method,
+            cancelled: None,
+        }
     }
 
     fn connect(
@@ -108,30 +122,53 @@ impl Connection for Client {
             #[cfg(not(any(feature = "native-tls", feature = "rustls")))]
             let maybe_connector = None;
 
-            let config = WebSocketConfig {
+            let config = address.config;
+            let ws_config = WebSocketConfig {
                 max_send_queue: match capacity {
                     0 => None,
                     capacity => Some(capacity),
                 },
-                max_message_size: Some(MAX_MESSAGE_SIZE),
-                max_frame_size: Some(MAX_FRAME_SIZE),
+                max_message_size: Some(config.max_message_size),
+                max_frame_size: Some(config.max_frame_size),
                 accept_unmasked_frames: false,
             };
 
-            let socket = connect(&url, Some(config), maybe_connector.clone()).await?;
+            let socket = connect(&url, Some(ws_config), maybe_connector.clone()).await?;
+            let events = Events::connected();
 
             let (route_tx, route_rx) = match capacity {
                 0 => flume::unbounded(),
                 capacity => flume::bounded(capacity),
             };
+            // unbounded so that dropping a request never blocks
+            let (cancel_tx, cancel_rx) = flume::unbounded();
+            let (shutdown_tx, shutdown_rx) = flume::bounded(1);
 
-            router(url, maybe_connector, capacity, config, socket, route_rx);
+            router(
+                url,
+                maybe_connector,
+                capacity,
+                config,
+                ws_config,
+                socket,
+                route_rx,
+                cancel_rx,
+                shutdown_tx,
+                events.clone(),
+            );
 
             Ok(Surreal {
                 router: OnceCell::with_value(Arc::new(Router {
                     conn: PhantomData,
                     sender: route_tx,
+                    timeout: config.request_timeout,
+                    events,
+                    requests: Requests::new(),
+                    session: Session::new(),
+                    owner: None,
+                    shutdown: Some(shutdown_rx),
                     last_id: AtomicI64::new(0),
+                    cancelled: Some(cancel_tx),
                 })),
             })
         })
@@ -149,6 +186,7 @@ impl Connection for Client {
                 request: (self.id, self.method, param),
                 response: sender,
             };
+            self.cancelled = router.cancelled.clone();
             router.sender.send_async(Some(route)).await?;
             Ok(receiver)
         })
@@ -162,8 +200,10 @@ impl Connection for Client {
         R: DeserializeOwned,
     {
         Box::pin(async move {
-            let response = rx.into_recv_async().await?;
-            match response? {
+            let response = rx.into_recv_async().await;
+            // the router is done with the request once it responds
+            self.cancelled = None;
+            match response?? {
                 DbResponse::Other(value) => from_value(&value),
                 DbResponse::Query(..) => unreachable!(),
             }
@@ -175,23 +215,29 @@ impl Connection for Client {
         rx: Receiver<Self::Response>,
     ) -> Pin<Box<dyn Future<Output = Result<QueryResponse>> + Send + Sync + '_>> {
         Box::pin(async move {
-            let response = rx.into_recv_async().await?;
-            match response? {
-                DbResponse::Query(results) => Ok(results),
+            let response = rx.into_recv_async().await;
+            // the router is done with the request once it responds
+            self.cancelled = None;
+            match response?? {
+                DbResponse::Query(response) => Ok(response),
                 DbResponse::Other(..) => unreachable!(),
             }
         })
     }
 }
 
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 fn router(
     url: Url,
     maybe_connector: Option<Connector>,
     capacity: usize,
-    config: WebSocketConfig,
+    config: Config,
+    ws_config: WebSocketConfig,
     mut socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
     route_rx: Receiver<Option<WsRoute>>,
+    cancel_rx: Receiver<i64>,
+    shutdown_tx: Sender<Result<()>>,
+    events: Arc<Events>,
 ) {
     tokio::spawn(async move {
         let ping = {
@@ -203,166 +249,296 @@ fn router(
 
         let mut vars = IndexMap::new();
         let mut replay = IndexMap::new();
+        let mut live_queries = LiveQueries::default();
+
+        let mut resend = Vec::new();
 
         'router: loop {
-            let (socket_sink, socket_stream) = socket.split();
-            let mut socket_sink = Socket(Some(socket_sink));
+            let (mut socket_sink, socket_stream) = socket.split();
 
-            if let Socket(Some(socket_sink)) = &mut socket_sink {
-                let mut routes = match capacity {
-                    0 => HashMap::new(),
-                    capacity => HashMap::with_capacity(capacity),
-                };
+            let mut routes = match capacity {
+                0 => HashMap::new(),
+                capacity => HashMap::with_capacity(capacity),
+            };
+            let mut live_requests = HashMap::new();
+            let mut reads = HashMap::new();
+            for (id, method, message, response) in mem::take(&mut resend) {
+                match socket_sink.send(Message::clone(&message)).await {
+                    Ok(..) => {
+                        routes.insert(id, (method, response));
+                        reads.insert(id, message);
+                    }
+                    Err(error) => {
+                        let error = ErrorKind::Socket.with_message(error.to_string());
+                        let _res = response.into_send_async(Err(error)).await;
+                    }
+                }
+            }
 
-                let mut interval = time::interval(PING_INTERVAL);
-                // don't bombard the server with pings if we miss some ticks
-                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
-                // Delay sending the first ping
-                interval.tick().await;
+            let ping_interval = config.ping_interval.max(MIN_PING_INTERVAL);
+            let mut interval = time::interval(ping_interval);
+            // don't bombard the server with pings if we miss some ticks
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // Delay sending the first ping
+            interval.tick().await;
 
-                let pinger = IntervalStream::new(interval);
+            let pinger = IntervalStream::new(interval);
 
-                let streams = (
-                    socket_stream.map(Either::Response),
-                    route_rx.stream().map(Either::Request),
-                    pinger.map(|_| Either::Ping),
-                );
+            let streams = (
+                socket_stream.map(Either::Response),
+                route_rx.stream().map(Either::Request),
+                cancel_rx.stream().map(Either::Cancelled),
+                pinger.map(|_| Either::Ping),
+            );
 
-                let mut merged = streams.merge();
-                let mut last_activity = Instant::now();
+            let mut merged = streams.merge();
+            let mut last_activity = Instant::now();
+            let mut last_received = Instant::now();
 
-                while let Some(either) = merged.next().await {
-                    match either {
-                        Either::Request(Some(Route { request, response })) => {
-                            let (id, method, param) = request;
-                            let params = param.query;
-                            match method {
-                                Method::Set => {
-                                    if let [Value::Strand(Strand(key)), value] = &params[..2] {
-                                        vars.insert(key.clone(), value.clone());
-                                    }
-                                }
-                                Method::Unset => {
-                                    if let [Value::Strand(Strand(key))] = &params[..1] {
-                                        vars.remove(key);
-                                    }
+            let mut reason = "the connection closed".to_owned();
+            while let Some(either) = merged.next().await {
+                match either {
+                    Either::Request(Some(Route { request, response })) => {
+                        let (id, method, param) = request;
+                        let mut params = param.query;
+                        match method {
+                            Method::Set => {
+                                if let [Value::Strand(Strand(key)), value] = &params[..] {
+                                    vars.insert(key.clone(), value.clone());
                                 }
-                                _ => {}
                             }
-                            let method_str = match method {
-                                Method::Health => PING_METHOD,
-                                _ => method.as_str(),
-                            };
-                            let message = {
-                                let mut request = BTreeMap::new();
-                                request.insert("id".to_owned(), Value::from(id));
-                                request.insert("method".to_owned(), method_str.into());
-                                if !params.is_empty() {
-                                    request.insert("params".to_owned(), params.into());
+                            Method::Unset => {
+                                if let [Value::Strand(Strand(key))] = &params[..] {
+                                    vars.remove(key);
                                 }
-                                let payload = Value::from(request);
-                                tracing::trace!("Request {payload}");
-                                Message::Binary(payload.into())
-                            };
-                            if let Method::Authenticate
-                            | Method::Invalidate
-                            | Method::Signin
-                            | Method::Signup
-                            | Method::Use = method
-                            {
+                            }
+                            Method::Kill => {
+                                live_queries.kill(&mut params[..]);
+                            }
+                            _ => {}
+                        }
+                        let live_request = param
+                            .notification_sender
+                            .map(|sender| (params.clone(), sender));
+                        let method_str = match method {
+                            Method::Health => PING_METHOD,
+                            _ => method.as_str(),
+                        };
+                        let message = {
+                            let mut request = BTreeMap::new();
+                            request.insert("id".to_owned(), Value::from(id));
+                            request.insert("method".to_owned(), method_str.into());
+                            if !params.is_empty() {
+                                request.insert("params".to_owned(), params.into());
+                            }
+                            let payload = Value::from(request);
+                            tracing::trace!("Request {payload}");
+                            Message::Binary(payload.into())
+                        };
+                        match method {
+                            // only the latest way of authenticating is replayed
+                            Method::Authenticate | Method::Signin | Method::Signup => {
+                                replay.retain(|method, _| *method == Method::Use);
                                 replay.insert(method, message.clone());
                             }
-                            match socket_sink.send(message).await {
-                                Ok(..) => {
-                                    last_activity = Instant::now();
-                                    match routes.entry(id) {
-                                        Entry::Vacant(entry) => {
-                                            entry.insert((method, response));
+                            // a new connection starts out signed out anyway
+                            Method::Invalidate => {
+                                replay.retain(|method, _| *method == Method::Use);
+                            }
+                            Method::Use => {
+                                replay.insert(method, message.clone());
+                            }
+                            _ => {}
+                        }
+                        let read = (config.resend_reads && is_idempotent_read(method))
+                            .then(|| message.clone());
+                        match socket_sink.send(message).await {
+                            Ok(..) => {
+                                last_activity = Instant::now();
+                                match routes.entry(id) {
+                                    Entry::Vacant(entry) => {
+                                        entry.insert((method, response));
+                                        if let Some(message) = read {
+                                            reads.insert(id, message);
                                         }
-                                        Entry::Occupied(..) => {
-                                            let error =
-                                                ErrorKind::DuplicateRequestId.with_context(id);
-                                            if response.into_send_async(Err(error)).await.is_err() {
-                                                tracing::trace!("Receiver dropped");
-                                            }
+                                        if let Some(live_request) = live_request {
+                                            live_requests.insert(id, live_request);
                                         }
                                     }
-                                }
-                                Err(error) => {
-                                    let error = ErrorKind::Socket.with_message(error.to_string());
-                                    if response.into_send_async(Err(error)).await.is_err() {
-                                        tracing::trace!("Receiver dropped");
+                                    Entry::Occupied(..) => {
+                                        let error = ErrorKind::DuplicateRequestId.with_context(id);
+                                        if response.into_send_async(Err(error)).await.is_err() {
+                                            tracing::trace!("Receiver dropped");
+                                        }
                                     }
-                                    break;
                                 }
                             }
+                            Err(error) => {
+                                let error = ErrorKind::Socket.with_message(error.to_string());
+                                reason = error.to_string();
+                                if response.into_send_async(Err(error)).await.is_err() {
+                                    tracing::trace!("Receiver dropped");
+                                }
+                                break;
+                            }
                         }
-                        Either::Response(result) => {
-                            last_activity = Instant::now();
-                            match result {
-                                Ok(message) => match Response::try_from(message) {
-                                    Ok(option) => {
-                                        if let Some(response) = option {
-                                            tracing::trace!("{response:?}");
-                                            if let Some(id) = response.id {
-                                                if let Some((method, sender)) =
-                                                    routes.remove(&id.as_int())
+                    }
+                    Either::Response(result) => {
+                        last_activity = Instant::now();
+                        last_received = last_activity;
+                        match result {
+                            Ok(message) => match Response::try_from(message) {
+                                Ok(option) => {
+                                    if let Some(response) = option {
+                                        tracing::trace!("{response:?}");
+                                        match response.id {
+                                            Some(id) => {
+                                                let id = id.as_int();
+                                                if let Some((method, sender)) = routes.remove(&id) {
+                                                    reads.remove(&id);
+                                                    let live_request = live_requests.remove(&id);
+                                                    // skip responses nobody is waiting for
+                                                    if sender.is_disconnected() {
+                                                        tracing::trace!("Receiver dropped");
+                                                        continue;
+                                                    }
+                                                    let response = DbResponse::from((
+                                                        method,
+                                                        response.content,
+                                                    ));
+                                                    if let Some(live_request) = live_request {
+                                                        live_queries
+                                                            .insert(live_request, &response);
+                                                    }
+                                                    let _res =
+                                                        sender.into_send_async(response).await;
+                                                }
+                                            }
+                                            // live query notifications are sent without an ID
+                                            None => {
+                                                if let Some(notification) =
+                                                    response.content.into_notification()
                                                 {
-                                                    let _res = sender
-                                                        .into_send_async(DbResponse::from((
-                                                            method,
-                                                            response.content,
-                                                        )))
-                                                        .await;
+                                                    live_queries.notify(notification).await;
                                                 }
                                             }
                                         }
                                     }
-                                    Err(_error) => {
-                                        tracing::trace!("Failed to deserialise message");
+                                }
+                                Err(_error) => {
+                                    tracing::trace!("Failed to deserialise message");
+                                }
+                            },
+                            Err(error) => {
+                                reason = error.to_string();
+                                match error {
+                                    WsError::ConnectionClosed => {
+                                        tracing::trace!(
+                                            "Connection successfully closed on the server"
+                                        );
                                     }
-                                },
-                                Err(error) => {
-                                    match error {
-                                        WsError::ConnectionClosed => {
-                                            tracing::trace!(
-                                                "Connection successfully closed on the server"
-                                            );
-                                        }
-                                        error => {
-                                            tracing::trace!("{error}");
-                                        }
+                                    error => {
+                                        tracing::trace!("{error}");
                                     }
-                                    break;
                                 }
+                                break;
                             }
                         }
-                        Either::Ping => {
-                            // only ping if we haven't talked to the server recently
-                            if last_activity.elapsed() >= PING_INTERVAL {
-                                tracing::trace!("Pinging the server");
-                                if let Err(error) = socket_sink.send(ping.clone()).await {
-                                    tracing::trace!("failed to ping the server; {error:?}");
-                                    break;
-                                }
+                    }
+                    Either::Cancelled(id) => {
+                        // the caller stopped waiting before the server responded
+                        if routes.remove(&id).is_some() {
+                            live_requests.remove(&id);
+                            reads.remove(&id);
+                        }
+                    }
+                    Either::Ping => {
+                        // forget requests whose callers stopped waiting, e.g. because they timed out
+                        routes.retain(|_, (_, sender)| !sender.is_disconnected());
+                        live_requests.retain(|id, _| routes.contains_key(id));
+                        reads.retain(|id, _| routes.contains_key(id));
+                        if let Some(timeout) = config.idle_timeout {
+                            if last_received.elapsed() >= timeout {
+                                tracing::trace!("Heard nothing from the server in {timeout:?}");
+                                reason = format!("heard nothing from the server in {timeout:?}");
+                                break;
+                            }
+                        }
+                        // only ping if we haven't talked to the server recently
+                        if last_activity.elapsed() >= ping_interval {
+                            tracing::trace!("Pinging the server");
+                            if let Err(error) = socket_sink.send(ping.clone()).await {
+                                tracing::trace!("failed to ping the server; {error:?}");
+                                reason = format!("failed to ping the server; {error}");
+                                break;
+                            }
+                        }
+                    }
+                    Either::Request(None) => {
+                        let mut errors = Vec::new();
+                        for request in live_queries.kill_all() {
+                            tracing::trace!("Request {request}");
+                            if let Err(error) =
+                                socket_sink.send(Message::Binary(request.into())).await
+                            {
+                                tracing::trace!("Failed to kill live query; {error}");
+                                errors.push(format!("failed to kill a live query; {error}"));
                             }
                         }
-                        Either::Request(None) => {
-                            break 'router;
+                        for (_, (_, response)) in routes {
+                            let _res = response.into_send_async(Err(connection_closed())).await;
                         }
+                        match socket_sink.close().await {
+                            Ok(..) => tracing::trace!("Connection closed successfully"),
+                            Err(error) => {
+                                tracing::trace!("Failed to close database connection; {error}");
+                                errors.push(format!("failed to close the connection; {error}"));
+                            }
+                        }
+                        let _res = shutdown_tx.send(shutdown_result(errors));
+                        break 'router;
+                    }
+                }
+            }
+            events.emit(ConnectionEvent::Disconnected { reason });
+            // requests the server didn't respond to can't be answered on a new connection
+            for (id, (method, response)) in routes {
+                match reads.remove(&id) {
+                    Some(message) => resend.push((id, method, message, response)),
+                    None => {
+                        let _res = response.into_send_async(Err(connection_lost())).await;
                     }
                 }
             }
 
+            let mut attempt = 0;
             'reconnect: loop {
+                let delay = match config.reconnect.delay(attempt) {
+                    Some(delay) => delay,
+                    None => {
+                        tracing::trace!("Giving up on reconnecting to the server");
+                        events.emit(ConnectionEvent::Closed);
+                        for (.., response) in resend.drain(..) {
+                            let _res = response.into_send_async(Err(connection_lost())).await;
+                        }
+                        for route in route_rx.drain().flatten() {
+                            let _res = route.response.into_send_async(Err(connection_lost())).await;
+                        }
+                        break 'router;
+                    }
+                };
+                attempt += 1;
+                events.emit(ConnectionEvent::Reconnecting { attempt });
+                if !delay.is_zero() {
+                    time::sleep(delay).await;
+                }
                 tracing::trace!("Reconnecting...");
-                match connect(&url, Some(config), maybe_connector.clone()).await {
+                match connect(&url, Some(ws_config), maybe_connector.clone()).await {
                     Ok(s) => {
                         socket = s;
                         for (_, message) in &replay {
                             if let Err(error) = socket.send(message.clone()).await {
                                 tracing::trace!("{error}");
-                                time::sleep(time::Duration::from_secs(1)).await;
                                 continue 'reconnect;
                             }
                         }
@@ -378,16 +554,40 @@ fn router(
                             tracing::trace!("Request {payload}");
                             if let Err(error) = socket.send(Message::Binary(payload.into())).await {
                                 tracing::trace!("{error}");
-                                time::sleep(time::Duration::from_secs(1)).await;
                                 continue 'reconnect;
                             }
                         }
+                        let mut relived = HashMap::with_capacity(live_queries.0.len());
+                        for (request_id, live_query) in (1..).zip(live_queries.0.values()) {
+                            // negative IDs can't clash with those of the requests replayed above
+                            match relive(&mut socket, -request_id, live_query.params.clone()).await
+                            {
+                                Ok(Ok(query_id)) => {
+                                    relived.insert(query_id, live_query.clone());
+                                }
+                                Ok(Err(error)) => {
+                                    tracing::trace!("Failed to re-register live query; {error}");
+                                    let _res = live_query.sender.send_async(Err(error)).await;
+                                }
+                                Err(error) => {
+                                    tracing::trace!("{error}");
+                                    continue 'reconnect;
+                                }
+                            }
+                        }
+                        for live_query in relived.values() {
+                            let _res = live_query
+                                .sender
+                                .send_async(Err(notifications_missed()))
+                                .await;
+                        }
+                        live_queries.0 = relived;
                         tracing::trace!("Reconnected successfully");
+                        events.emit(ConnectionEvent::Reconnected);
                         break;
                     }
                     Err(error) => {
                         tracing::trace!("Failed to reconnect; {error}");
-                        time::sleep(time::Duration::from_secs(1)).await;
                     }
                 }
             }
@@ -395,6 +595,28 @@ fn router(
     });
 }
 
+/// Starts a live query again on a new connection, returning the ID the server assigned to it
+///
+/// The outer result fails if the connection itself failed, the inner one if the server
+/// refused to start the live query.
+async fn relive(
+    socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    id: i64,
+    params: Vec<Value>,
+) -> Result<Result<Uuid>> {
+    let request = relive_request(id, params);
+    tracing::trace!("Request {request}");
+    socket.send(Message::Binary(request.into())).await?;
+    while let Some(result) = socket.next().await {
+        if let Ok(Some(response)) = Response::try_from(result?) {
+            if let Some(result) = response.relived(id) {
+                return Ok(result);
+            }
+        }
+    }
+    Err(ErrorKind::Socket.with_message("connection closed while starting live queries"))
+}
+
 impl Response {
     fn try_from(message: Message) -> Result<Option<Self>> {
         match message {
@@ -424,18 +646,3 @@ impl Response {
         }
     }
 }
-
-pub struct Socket(Option<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>);
-
-impl Drop for Socket {
-    fn drop(&mut self) {
-        if let Some(mut conn) = mem::take(&mut self.0) {
-            futures::executor::block_on(async move {
-                match conn.borrow_mut().close().await {
-                    Ok(..) => tracing::trace!("Connection closed successfully"),
-                    Err(error) => tracing::trace!("Failed to close database connection; {error}"),
-                }
-            });
-        }
-    }
-}
@@ -0,0 +1,190 @@
+use crate::param::QueryResult;
+use crate::Error;
+use crate::Result;
+use serde::Deserialize;
+use std::slice;
+use std::time::Duration;
+use std::vec;
+use surrealdb::sql::Value;
+
+/// The response to a query
+///
+/// Holds one result per statement in the query.
+#[derive(Debug, Clone, Default)]
+pub struct Response {
+    pub(crate) results: Vec<Result<Vec<Value>>>,
+    pub(crate) stats: Vec<Stats>,
+}
+
+/// Whether a statement succeeded
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Status {
+    /// The statement succeeded
+    Ok,
+    /// The statement failed
+    Err,
+}
+
+/// Statistics the server reports about the execution of a statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Stats {
+    /// How long the server took to execute the statement
+    ///
+    /// This is `None` if the server did not report it.
+    pub execution_time: Option<Duration>,
+    /// Whether the statement succeeded
+    pub status: Status,
+}
+
+impl Response {
+    /// Takes and deserializes the result of a statement
+    ///
+    /// The result is removed from the response so taking it again yields an empty result.
+    /// Taking the result of a statement that failed returns its error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use serde::Deserialize;
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[derive(Deserialize)]
+    /// # struct User {
+    /// #     name: String,
+    /// # }
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let mut response = client
+    ///     .query("SELECT * FROM user:john")
+    ///     .query("SELECT * FROM user")
+    ///     .query("SELECT * FROM user")
+    ///     .await?;
+    ///
+    /// // a single record
+    /// let john: Option<User> = response.take(0)?;
+    ///
+    /// // all the records returned by the second statement
+    /// let users: Vec<User> = response.take(1)?;
+    ///
+    /// // just the names of the users returned by the third statement
+    /// let names: Vec<String> = response.take((2, "name"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn take<R>(&mut self, index: impl QueryResult<R>) -> Result<R> {
+        index.query_result(self)
+    }
+
+    /// Returns the first error returned by the statements in the response, if any
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// client
+    ///     .query("CREATE user:john SET name = 'John Doe'")
+    ///     .await?
+    ///     .check()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check(mut self) -> Result<Self> {
+        if let Some(index) = self.results.iter().position(Result::is_err) {
+            if let Err(error) = self.results.swap_remove(index) {
+                return Err(error);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the execution statistics of the statements in the response
+    ///
+    /// The statistics are in the same order as the statements. They remain available after
+    /// the results are taken.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use surrealdb_rs::{Result, Surreal};
+    /// # use surrealdb_rs::net::WsClient;
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Surreal::<WsClient>::new();
+    /// let response = client.query("SELECT * FROM user").await?;
+    /// for (index, stats) in response.stats().iter().enumerate() {
+    ///     if let Some(time) = stats.execution_time {
+    ///         if time > Duration::from_millis(100) {
+    ///             println!("statement {index} took {time:?}");
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> &[Stats] {
+        &self.stats
+    }
+
+    /// Returns the number of statements in the response
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns `true` if the response has no statements
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Returns the raw results of the statements in the response
+    pub fn into_inner(self) -> Vec<Result<Vec<Value>>> {
+        self.results
+    }
+}
+
+impl From<Response> for Vec<Result<Vec<Value>>> {
+    fn from(response: Response) -> Self {
+        response.results
+    }
+}
+
+impl From<Vec<Result<Vec<Value>>>> for Response {
+    fn from(results: Vec<Result<Vec<Value>>>) -> Self {
+        let stats = results
+            .iter()
+            .map(|result| Stats {
+                execution_time: None,
+                status: match result {
+                    Ok(..) => Status::Ok,
+                    Err(..) => Status::Err,
+                },
+            })
+            .collect();
+        Self { results, stats }
+    }
+}
+
+impl IntoIterator for Response {
+    type Item = Result<Vec<Value>>;
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Response {
+    type Item = &'a Result<Vec<Value>, Error>;
+    type IntoIter = slice::Iter<'a, Result<Vec<Value>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.iter()
+    }
+}
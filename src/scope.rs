@@ -0,0 +1,197 @@
+//! Scopes the requests of sessions to their own namespace, database and parameters
+//!
+//! The server keeps a single namespace, database and set of parameters per connection, which
+//! the sessions sharing it can't change without affecting each other. Instead, their
+//! requests are sent as queries that select the namespace and database of the session
+//! before anything else, with the parameters of the session bound to them. A `USE` statement
+//! only applies to the rest of the query it's part of, so requests in flight for other
+//! sessions keep their own.
+
+use crate::method::Method;
+use crate::param::from_value;
+use crate::param::Param;
+use crate::protocol::statement::create_statement;
+use crate::protocol::statement::delete_statement;
+use crate::protocol::statement::merge_statement;
+use crate::protocol::statement::patch_statement;
+use crate::protocol::statement::select_statement;
+use crate::protocol::statement::update_statement;
+use crate::Connection;
+use crate::ErrorKind;
+use crate::Response;
+use crate::Result;
+use crate::Router;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::mem;
+use std::time::Duration;
+use surrealdb::sql::Object;
+use surrealdb::sql::Strand;
+use surrealdb::sql::Value;
+
+/// The namespace, database and parameters a session selected
+#[derive(Debug, Default)]
+pub(crate) struct Scope {
+    pub(crate) namespace: Option<String>,
+    pub(crate) database: Option<String>,
+    pub(crate) vars: BTreeMap<String, Value>,
+}
+
+impl<C> Router<C>
+where
+    C: Connection,
+{
+    /// Executes all methods except `query`
+    ///
+    /// Sessions create, read, update and delete records with statements in their own scope.
+    pub(crate) async fn execute<R>(&self, method: Method, param: Param) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        let scoped = matches!(
+            method,
+            Method::Create
+                | Method::Update
+                | Method::Patch
+                | Method::Merge
+                | Method::Select
+                | Method::Delete
+        );
+        if self.owner.is_none() || !scoped {
+            let mut conn = C::new(method);
+            return conn.execute(self, param).await;
+        }
+        let mut params = param.query;
+        let (one, statement) = match method {
+            Method::Create => (true, create_statement(&mut params).to_string()),
+            Method::Update => {
+                let (one, statement) = update_statement(&mut params);
+                (one, statement.to_string())
+            }
+            Method::Patch => {
+                let (one, statement) = patch_statement(&mut params);
+                (one, statement.to_string())
+            }
+            Method::Merge => {
+                let (one, statement) = merge_statement(&mut params);
+                (one, statement.to_string())
+            }
+            Method::Select => {
+                let (one, statement) = select_statement(&mut params);
+                (one, statement.to_string())
+            }
+            Method::Delete => (true, delete_statement(&mut params).to_string()),
+            _ => unreachable!(),
+        };
+        let response = self
+            .query_in_scope(statement, BTreeMap::new(), param.timeout)
+            .await?;
+        let values = match response.results.into_iter().next() {
+            Some(result) => result?,
+            None => Vec::new(),
+        };
+        // shaped the same way as the responses of the protocols that send statements
+        let value = match one {
+            true => match <[Value; 1]>::try_from(values) {
+                Ok([value]) => value,
+                Err(..) => Value::None,
+            },
+            false => values.into(),
+        };
+        from_value(&value)
+    }
+
+    /// Executes the `query` method
+    pub(crate) async fn execute_query(&self, param: Param) -> Result<Response> {
+        if self.owner.is_none() {
+            let mut conn = C::new(Method::Query);
+            return conn.execute_query(self, param).await;
+        }
+        let mut params = param.query;
+        let (statements, bindings) = match &mut params[..] {
+            [Value::Strand(Strand(statements))] => (mem::take(statements), BTreeMap::new()),
+            [Value::Strand(Strand(statements)), Value::Object(Object(bindings))] => {
+                (mem::take(statements), mem::take(bindings))
+            }
+            _ => unreachable!(),
+        };
+        self.query_in_scope(statements, bindings, param.timeout)
+            .await
+    }
+
+    /// Runs statements in the namespace and database of the session, with its parameters
+    ///
+    /// A session that didn't select a namespace and database runs them in those of the
+    /// connection. Bindings of the query take precedence over parameters of the session.
+    async fn query_in_scope(
+        &self,
+        statements: String,
+        bindings: BTreeMap<String, Value>,
+        timeout: Option<Duration>,
+    ) -> Result<Response> {
+        let Scope {
+            namespace,
+            database,
+            mut vars,
+        } = self.session.scope();
+        let (statements, prefixed) = match (namespace, database) {
+            (Some(ns), Some(db)) => {
+                let ns = ident(&ns)?;
+                let db = ident(&db)?;
+                (format!("USE NS {ns} DB {db}; {statements}"), true)
+            }
+            _ => (statements, false),
+        };
+        vars.extend(bindings);
+        let mut param = vec![statements.into()];
+        if !vars.is_empty() {
+            param.push(vars.into());
+        }
+        let mut conn = C::new(Method::Query);
+        let mut response = conn
+            .execute_query(self, Param::new(param).timeout(timeout))
+            .await?;
+        // the caller didn't ask for the result of `USE`
+        if prefixed && !response.results.is_empty() {
+            response.results.remove(0)?;
+            if !response.stats.is_empty() {
+                response.stats.remove(0);
+            }
+        }
+        Ok(response)
+    }
+}
+
+/// Writes a namespace or database name as an identifier, escaped if it needs to be
+pub(crate) fn ident(name: &str) -> Result<String> {
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        return Ok(name.to_owned());
+    }
+    // identifiers in backticks end at the next backtick, there is no escaping it
+    if name.contains('`') {
+        return Err(ErrorKind::InvalidParams.with_message(format!(
+            "the name `{name}` can't be used by a session because it contains a backtick"
+        )));
+    }
+    Ok(format!("`{name}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_names_are_left_alone() {
+        assert_eq!(ident("tenant_a").unwrap(), "tenant_a");
+    }
+
+    #[test]
+    fn other_names_are_escaped() {
+        assert_eq!(ident("tenant-a").unwrap(), "`tenant-a`");
+        assert_eq!(ident("1tenant").unwrap(), "`1tenant`");
+        let error = ident("tenant`a").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidParams);
+    }
+}
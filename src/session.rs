@@ -0,0 +1,313 @@
+use crate::param::Jwt;
+use crate::param::SavedSession;
+use crate::param::SessionStore;
+use crate::scope::Scope;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Result;
+use futures::lock::Mutex as AsyncMutex;
+use futures::lock::MutexGuard;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+use surrealdb::sql::Value;
+
+/// How long before the token expires the client gets a new one
+const REFRESH_MARGIN: Duration = Duration::from_secs(10);
+
+/// Hands out a new token when the current one expires
+pub(crate) type RefreshCallback =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<Jwt>> + Send + Sync>> + Send + Sync>;
+
+/// How the client signed in, so that it can do so again once its token expires, and what it
+/// needs to restore the session after a restart
+pub(crate) struct Session {
+    state: Mutex<State>,
+    /// Held while refreshing so that concurrent requests don't all refresh at once
+    refreshing: AsyncMutex<()>,
+    /// The version of the session that was saved last
+    saved: Arc<Mutex<u64>>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Whether the client is signed in
+    active: bool,
+    /// The credentials the client last signed in with
+    credentials: Option<Value>,
+    callback: Option<RefreshCallback>,
+    /// The token the client is authenticated with
+    token: Option<Jwt>,
+    /// When the current token expires
+    expires_at: Option<SystemTime>,
+    /// Bumped whenever the client gets a new token
+    generation: u64,
+    namespace: Option<String>,
+    database: Option<String>,
+    vars: BTreeMap<String, Value>,
+    /// Where the session is saved whenever it changes
+    store: Option<Arc<dyn SessionStore>>,
+    /// Bumped whenever the session changes, so that saves which finish out of order don't
+    /// overwrite a newer session with an older one
+    version: u64,
+}
+
+/// A snapshot of the session, taken under the lock and saved once it's released
+struct Save {
+    store: Arc<dyn SessionStore>,
+    version: u64,
+    session: SavedSession,
+}
+
+/// A way to get a new token
+pub(crate) enum Refresh {
+    Signin(Value),
+    Callback(RefreshCallback),
+}
+
+impl Session {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+            refreshing: AsyncMutex::new(()),
+            saved: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    /// Records a successful signin
+    pub(crate) async fn signed_in(&self, credentials: Value, token: Option<&Jwt>) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            state.credentials = Some(credentials);
+            state.renew(token)
+        };
+        self.save(save).await;
+    }
+
+    /// Records a successful signup or authentication, which can't be repeated with credentials
+    pub(crate) async fn authenticated(&self, token: Option<&Jwt>) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            state.credentials = None;
+            state.renew(token)
+        };
+        self.save(save).await;
+    }
+
+    /// Records a new token for the same user
+    pub(crate) async fn refreshed(&self, token: Option<&Jwt>) {
+        let save = self.state.lock().unwrap().renew(token);
+        self.save(save).await;
+    }
+
+    /// Records that the client signed out
+    pub(crate) async fn invalidated(&self) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            state.active = false;
+            state.credentials = None;
+            state.token = None;
+            state.expires_at = None;
+            state.generation += 1;
+            state.to_save()
+        };
+        self.save(save).await;
+    }
+
+    /// Records a switch to another namespace or database
+    pub(crate) async fn used(&self, namespace: Option<String>, database: Option<String>) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            if namespace.is_some() {
+                state.namespace = namespace;
+            }
+            if database.is_some() {
+                state.database = database;
+            }
+            state.to_save()
+        };
+        self.save(save).await;
+    }
+
+    pub(crate) async fn var_set(&self, key: String, value: Value) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            state.vars.insert(key, value);
+            state.to_save()
+        };
+        self.save(save).await;
+    }
+
+    pub(crate) async fn var_unset(&self, key: &str) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            state.vars.remove(key);
+            state.to_save()
+        };
+        self.save(save).await;
+    }
+
+    /// The namespace, database and parameters the session selected
+    pub(crate) fn scope(&self) -> Scope {
+        let state = self.state.lock().unwrap();
+        Scope {
+            namespace: state.namespace.clone(),
+            database: state.database.clone(),
+            vars: state.vars.clone(),
+        }
+    }
+
+    /// Saves the session into `store` now and whenever it changes from now on
+    pub(crate) async fn set_store(&self, store: Arc<dyn SessionStore>) {
+        let save = {
+            let mut state = self.state.lock().unwrap();
+            state.store = Some(store);
+            state.to_save()
+        };
+        self.save(save).await;
+    }
+
+    /// Saves a snapshot of the session, unless a newer one was saved already
+    ///
+    /// Stores may block, so they are only called once the state is unlocked, and natively on
+    /// a thread that is allowed to block.
+    async fn save(&self, save: Option<Save>) {
+        let save = match save {
+            Some(save) => save,
+            None => return,
+        };
+        let saved = self.saved.clone();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(error) = tokio::task::spawn_blocking(move || save.run(&saved)).await {
+            tracing::warn!("Failed to save the session; {error}");
+        }
+        #[cfg(target_arch = "wasm32")]
+        save.run(&saved);
+    }
+
+    pub(crate) fn set_callback(&self, callback: RefreshCallback) {
+        self.state.lock().unwrap().callback = Some(callback);
+    }
+
+    /// How to get a new token, if the client knows of a way
+    ///
+    /// The refresh callback takes precedence over signing in again.
+    pub(crate) fn refresh(&self) -> Option<Refresh> {
+        let state = self.state.lock().unwrap();
+        if !state.active {
+            return None;
+        }
+        match (&state.callback, &state.credentials) {
+            (Some(callback), _) => Some(Refresh::Callback(callback.clone())),
+            (None, Some(credentials)) => Some(Refresh::Signin(credentials.clone())),
+            (None, None) => None,
+        }
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Whether the current token expires soon, or already has
+    pub(crate) fn expires_soon(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        match state.expires_at {
+            Some(expires_at) => SystemTime::now() + REFRESH_MARGIN >= expires_at,
+            None => false,
+        }
+    }
+
+    /// Whether the current token expires at all
+    pub(crate) fn expires(&self) -> bool {
+        self.state.lock().unwrap().expires_at.is_some()
+    }
+
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.refreshing.lock().await
+    }
+}
+
+impl State {
+    fn renew(&mut self, token: Option<&Jwt>) -> Option<Save> {
+        self.active = true;
+        self.token = token.cloned();
+        self.expires_at = token.and_then(Jwt::expires_at);
+        self.generation += 1;
+        self.to_save()
+    }
+
+    fn saved(&self) -> SavedSession {
+        SavedSession {
+            token: self.token.clone(),
+            namespace: self.namespace.clone(),
+            database: self.database.clone(),
+            vars: self.vars.clone(),
+        }
+    }
+
+    /// Takes a snapshot of the session if it's being saved
+    fn to_save(&mut self) -> Option<Save> {
+        let store = self.store.clone()?;
+        self.version += 1;
+        Some(Save {
+            store,
+            version: self.version,
+            session: self.saved(),
+        })
+    }
+}
+
+impl Save {
+    fn run(self, saved: &Mutex<u64>) {
+        let mut saved = saved.lock().unwrap();
+        if *saved >= self.version {
+            return;
+        }
+        if let Err(error) = self.store.save(&self.session) {
+            tracing::warn!("Failed to save the session; {error}");
+        }
+        *saved = self.version;
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session").finish_non_exhaustive()
+    }
+}
+
+/// The message the server fails requests with when their token is invalid or has expired
+const AUTH_FAILURE: &str = "There was a problem with authentication";
+
+/// Whether the server refused a request because the client is no longer authenticated
+pub(crate) fn is_auth_failure(error: &Error) -> bool {
+    error.kind() == ErrorKind::Query && error.to_string().ends_with(AUTH_FAILURE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_failures() {
+        let error = ErrorKind::Query.with_message(AUTH_FAILURE);
+        assert!(is_auth_failure(&error));
+        let error = ErrorKind::Query.with_context(AUTH_FAILURE);
+        assert!(is_auth_failure(&error));
+    }
+
+    #[test]
+    fn other_failures() {
+        let error = ErrorKind::Query.with_message("The record has expired");
+        assert!(!is_auth_failure(&error));
+        let error = ErrorKind::Query.with_message("authentication scope `user` does not exist");
+        assert!(!is_auth_failure(&error));
+        let error = ErrorKind::Socket.with_message(AUTH_FAILURE);
+        assert!(!is_auth_failure(&error));
+    }
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "mem")]
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde::Deserialize;
+use serde::Serialize;
+use surrealdb_rs::param::Env;
+use surrealdb_rs::protocol::Any;
+use surrealdb_rs::ErrorKind;
+use surrealdb_rs::Surreal;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    name: String,
+}
+
+#[tokio::test]
+async fn connect_by_scheme() {
+    let client = Surreal::connect::<Any>("mem://").await.unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    let john = User {
+        name: "John Doe".to_owned(),
+    };
+    let created: User = client
+        .create(("user", "john"))
+        .content(&john)
+        .await
+        .unwrap();
+    assert_eq!(created, john);
+    let mut response = client.query("SELECT * FROM user").await.unwrap();
+    let users: Vec<User> = response.take(0).unwrap();
+    assert_eq!(users, [john]);
+}
+
+#[tokio::test]
+async fn unsupported_scheme() {
+    let error = Surreal::connect::<Any>("foo://localhost:8000")
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParams);
+}
+
+#[tokio::test]
+async fn invalid_url() {
+    let error = Surreal::connect::<Any>("not a url".to_owned())
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ParseError);
+}
+
+#[tokio::test]
+async fn unknown_connection_option() {
+    let error = Surreal::connect::<Any>("ws://root:root@localhost:8000?ns=test&db=test&foo=bar")
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParams);
+    assert!(error.to_string().contains("`foo`"));
+}
+
+#[tokio::test]
+async fn namespace_without_database() {
+    let error = Surreal::connect::<Any>("ws://root:root@localhost:8000?ns=test")
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParams);
+}
+
+#[tokio::test]
+async fn namespace_user_without_namespace() {
+    let error = Surreal::connect::<Any>("ws://user:pass@localhost:8000?auth=ns")
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParams);
+}
+
+#[tokio::test]
+async fn endpoint_from_env() {
+    std::env::set_var("SURREALDB_RS_TEST_URL", "mem://");
+    let client = Surreal::connect::<Any>(Env("SURREALDB_RS_TEST_URL"))
+        .await
+        .unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    let error = Surreal::connect::<Any>(Env("SURREALDB_RS_TEST_UNSET"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParams);
+}
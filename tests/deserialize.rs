@@ -0,0 +1,92 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+use surrealdb::sql;
+use surrealdb::sql::Id;
+use surrealdb::sql::Value;
+use surrealdb_rs::param::from_value;
+use surrealdb_rs::param::to_value;
+use surrealdb_rs::param::RecordId;
+use surrealdb_rs::ErrorKind;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Name {
+    first: String,
+    last: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: RecordId,
+    name: Name,
+    created: sql::Datetime,
+    timeout: Duration,
+    balance: u128,
+    nickname: Option<String>,
+}
+
+fn user(first: &str) -> User {
+    User {
+        id: RecordId {
+            tb: "user".to_owned(),
+            id: Id::String(first.to_lowercase()),
+        },
+        name: Name {
+            first: first.to_owned(),
+            last: "Doe".to_owned(),
+        },
+        created: sql::Datetime::default(),
+        timeout: Duration::from_millis(1500),
+        balance: u128::from(u64::MAX) * 2,
+        nickname: None,
+    }
+}
+
+#[test]
+fn round_trip() {
+    let john = user("John");
+    let value = to_value(&john).unwrap();
+    let deserialized: User = from_value(&value).unwrap();
+    assert_eq!(deserialized, john);
+    let raw: Value = from_value(&value).unwrap();
+    assert_eq!(raw, value);
+}
+
+#[test]
+fn errors_report_the_path() {
+    let users = vec![user("John"), user("Jane"), user("Jim"), user("Jill")];
+    let mut value = to_value(&users).unwrap();
+    if let Value::Array(sql::Array(users)) = &mut value {
+        if let Value::Object(sql::Object(user)) = &mut users[3] {
+            if let Some(Value::Object(sql::Object(name))) = user.get_mut("name") {
+                name.insert("first".to_owned(), Value::from(42_i64));
+            }
+        }
+    }
+    let error = from_value::<Vec<User>>(&value).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Deserialization);
+    assert!(
+        error.to_string().contains("`result[3].name.first`"),
+        "{error}"
+    );
+}
+
+#[test]
+fn missing_fields_report_their_parent() {
+    let value = to_value(vec![Name {
+        first: "John".to_owned(),
+        last: "Doe".to_owned(),
+    }])
+    .unwrap();
+    let error = from_value::<Vec<User>>(&value).unwrap_err();
+    assert!(error.to_string().contains("`result[0]`"), "{error}");
+    assert!(error.to_string().contains("missing field"), "{error}");
+}
+
+#[test]
+fn geometries_round_trip() {
+    let point = Value::Geometry(sql::Geometry::Point((-0.118092, 51.509865).into()));
+    let value = to_value(vec![point.clone(), Value::from("London")]).unwrap();
+    let values: Vec<Value> = from_value(&value).unwrap();
+    assert_eq!(values, vec![point, Value::from("London")]);
+}
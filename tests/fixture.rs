@@ -0,0 +1,140 @@
+#![cfg(all(feature = "test-util", feature = "mem"))]
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::env;
+use std::path::PathBuf;
+use surrealdb::sql;
+use surrealdb::sql::Id;
+use surrealdb_rs::fixture;
+use surrealdb_rs::fixture::Record;
+use surrealdb_rs::mock::Mock;
+use surrealdb_rs::param::RecordId;
+use surrealdb_rs::protocol::Mem;
+use surrealdb_rs::ErrorKind;
+use surrealdb_rs::Surreal;
+use tokio::fs::remove_file;
+use ulid::Ulid;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct NewEvent {
+    created: sql::Datetime,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Event {
+    id: RecordId,
+    created: sql::Datetime,
+}
+
+fn path() -> PathBuf {
+    env::temp_dir().join(format!("surrealdb-rs-{}.msgpack", Ulid::new()))
+}
+
+#[tokio::test]
+async fn record_and_replay() {
+    let path = path();
+    let john = User {
+        name: "John Doe".to_owned(),
+    };
+
+    let client = Surreal::connect::<Record<Mem>>(((), &path)).await.unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    let created: User = client
+        .create(("user", "john"))
+        .content(&john)
+        .await
+        .unwrap();
+    assert_eq!(created, john);
+    let mut response = client.query("SELECT * FROM user").await.unwrap();
+    let users: Vec<User> = response.take(0).unwrap();
+    assert_eq!(users.len(), 1);
+    let error = client.version().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MethodNotSupported);
+    drop(client);
+
+    let server = fixture::replay(&path).unwrap();
+    let client = Surreal::connect::<Mock>(&server).await.unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    let created: User = client
+        .create(("user", "john"))
+        .content(&john)
+        .await
+        .unwrap();
+    assert_eq!(created, john);
+    let mut response = client.query("SELECT * FROM user").await.unwrap();
+    let users: Vec<User> = response.take(0).unwrap();
+    assert_eq!(users, [john]);
+    let error = client.version().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MethodNotSupported);
+    server.verify();
+
+    remove_file(path).await.unwrap();
+}
+
+#[tokio::test]
+async fn replay_keeps_surrealdb_types() {
+    let path = path();
+    let new_event = NewEvent {
+        created: sql::Datetime::default(),
+    };
+
+    let client = Surreal::connect::<Record<Mem>>(((), &path)).await.unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    let recorded: Event = client
+        .create(("event", "launch"))
+        .content(&new_event)
+        .await
+        .unwrap();
+    assert_eq!(
+        recorded,
+        Event {
+            id: RecordId {
+                tb: "event".to_owned(),
+                id: Id::String("launch".to_owned()),
+            },
+            created: new_event.created.clone(),
+        }
+    );
+    drop(client);
+
+    let server = fixture::replay(&path).unwrap();
+    let client = Surreal::connect::<Mock>(&server).await.unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    let replayed: Event = client
+        .create(("event", "launch"))
+        .content(&new_event)
+        .await
+        .unwrap();
+    assert_eq!(replayed, recorded);
+    server.verify();
+
+    remove_file(path).await.unwrap();
+}
+
+#[tokio::test]
+async fn replay_rejects_requests_with_other_params() {
+    let path = path();
+
+    let client = Surreal::connect::<Record<Mem>>(((), &path)).await.unwrap();
+    client.use_ns("test-ns").use_db("test-db").await.unwrap();
+    drop(client);
+
+    let server = fixture::replay(&path).unwrap();
+    let client = Surreal::connect::<Mock>(&server).await.unwrap();
+    let error = client
+        .use_ns("other-ns")
+        .use_db("test-db")
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidRequest);
+    assert!(error.to_string().contains("other-ns"), "{error}");
+
+    remove_file(path).await.unwrap();
+}
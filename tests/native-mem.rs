@@ -0,0 +1,509 @@
+#![cfg(feature = "mem")]
+#![cfg(not(target_arch = "wasm32"))]
+
+#[allow(dead_code)]
+mod types;
+
+use serde_json::json;
+use std::ops::Bound;
+use surrealdb::sql::statements::BeginStatement;
+use surrealdb::sql::statements::CommitStatement;
+use surrealdb_rs::param::PatchOp;
+use surrealdb_rs::param::Root;
+use surrealdb_rs::protocol::Mem;
+use surrealdb_rs::ErrorKind;
+use surrealdb_rs::Status;
+use surrealdb_rs::Surreal;
+use types::*;
+use ulid::Ulid;
+
+#[tokio::test]
+async fn connect() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.health().await.unwrap();
+}
+
+#[tokio::test]
+async fn connect_with_capacity() {
+    let client = Surreal::connect::<Mem>(())
+        .with_capacity(512)
+        .await
+        .unwrap();
+    client.health().await.unwrap();
+}
+
+#[tokio::test]
+async fn yuse() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+}
+
+#[tokio::test]
+async fn query() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.query("SELECT * FROM record").await.unwrap();
+}
+
+#[tokio::test]
+async fn query_binds() {
+    let user = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    client
+        .query("CREATE type::thing($table, john) SET name = $name")
+        .bind("table", user)
+        .bind("name", "John Doe")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn query_take() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut response = client
+        .query("CREATE type::thing($table, john) SET name = 'John Doe'")
+        .query("CREATE type::thing($table, jane) SET name = 'Jane Doe'")
+        .query("SELECT * FROM type::table($table)")
+        .query("SELECT name FROM type::table($table)")
+        .bind("table", table)
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    assert_eq!(response.len(), 4);
+    assert_eq!(response.stats().len(), 4);
+    for stats in response.stats() {
+        assert_eq!(stats.status, Status::Ok);
+        assert!(stats.execution_time.is_some());
+    }
+    let john: Option<RecordId> = response.take(0).unwrap();
+    assert!(john.is_some());
+    let name: Option<String> = response.take((1, "name")).unwrap();
+    assert_eq!(name.as_deref(), Some("Jane Doe"));
+    let records: Vec<RecordId> = response.take(2).unwrap();
+    assert_eq!(records.len(), 2);
+    let names: Vec<String> = response.take((3, "name")).unwrap();
+    assert_eq!(names.len(), 2);
+}
+
+#[tokio::test]
+async fn query_chaining() {
+    let account = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    client
+        .query(BeginStatement)
+        .query("CREATE type::thing($table, one) SET balance = 135605.16")
+        .query("CREATE type::thing($table, two) SET balance = 91031.31")
+        .query("UPDATE type::thing($table, one) SET balance += 300.00")
+        .query("UPDATE type::thing($table, two) SET balance -= 300.00")
+        .query(CommitStatement)
+        .bind("table", account)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn transaction_commit() {
+    let account = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut transaction = client.transaction().await.unwrap();
+    transaction
+        .create((account.as_str(), "one"))
+        .content(json!({ "balance": 135605.16 }));
+    transaction
+        .create((account.as_str(), "two"))
+        .content(json!({ "balance": 91031.31 }));
+    transaction
+        .update((account.as_str(), "one"))
+        .merge(json!({ "balance": 135905.16 }));
+    transaction
+        .query("UPDATE type::thing($table, two) SET balance -= 300.00")
+        .bind("table", &account);
+    transaction.select(account.as_str());
+    let results = transaction.commit().await.unwrap();
+    assert_eq!(results.len(), 5);
+    for result in results {
+        result.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn transaction_handles() {
+    let account = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut transaction = client.transaction().await.unwrap();
+    // a query with several statements shifts the results of the records that follow it
+    transaction
+        .query("CREATE type::thing($table, 'one'); CREATE type::thing($table, 'two')")
+        .bind("table", &account);
+    let one = transaction.select((account.as_str(), "one")).handle();
+    let all = transaction.select(account.as_str()).handle();
+    assert_eq!(one.index(), 2);
+    assert_eq!(all.index(), 3);
+    let mut response = transaction.commit().await.unwrap();
+    assert_eq!(response.len(), 4);
+    let one: Option<RecordId> = response.take(one).unwrap();
+    assert!(one.is_some());
+    let all: Vec<RecordId> = response.take(all).unwrap();
+    assert_eq!(all.len(), 2);
+}
+
+#[tokio::test]
+async fn transaction_cancel() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut transaction = client.transaction().await.unwrap();
+    transaction.create(table.as_str());
+    transaction.cancel().await.unwrap();
+    let records: Vec<RecordId> = client.select(table.as_str()).await.unwrap();
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn transaction_with() {
+    let account = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let results = client
+        .transaction_with(move |mut transaction| {
+            transaction
+                .query("CREATE type::thing($table, one) SET balance = 135605.16")
+                .query("UPDATE type::thing($table, one) SET balance += 300.00")
+                .bind("table", &account);
+            async move { Ok::<_, surrealdb_rs::Error>(transaction) }
+        })
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[tokio::test]
+async fn transaction_with_error() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let result =
+        client
+            .transaction_with({
+                let table = table.clone();
+                move |mut transaction| {
+                    transaction.create(table.as_str());
+                    async move {
+                        Err::<_, surrealdb_rs::Error>(ErrorKind::Query.with_message("aborted"))
+                    }
+                }
+            })
+            .await;
+    assert!(result.is_err());
+    let records: Vec<RecordId> = client.select(table.as_str()).await.unwrap();
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn create_record_no_id() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: RecordId = client.create(table).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_record_with_id() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: RecordId = client.create((table, "john")).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_record_no_id_with_content() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: RecordId = client
+        .create(table)
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn create_record_with_id_with_content() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let record: RecordId = client
+        .create((table.as_str(), "john"))
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+    assert_eq!(record.id, format!("{table}:john"));
+}
+
+#[tokio::test]
+async fn select_table() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client.select(table.as_str()).await.unwrap();
+}
+
+#[tokio::test]
+async fn select_record_id() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Option<RecordId> = client.select((table.as_str(), "john")).await.unwrap();
+}
+
+#[tokio::test]
+async fn select_record_ranges() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client.select(table.as_str()).range(..).await.unwrap();
+    let _: Vec<RecordId> = client.select(table.as_str()).range(.."john").await.unwrap();
+    let _: Vec<RecordId> = client
+        .select(table.as_str())
+        .range(..="john")
+        .await
+        .unwrap();
+    let _: Vec<RecordId> = client.select(table.as_str()).range("jane"..).await.unwrap();
+    let _: Vec<RecordId> = client
+        .select(table.as_str())
+        .range("jane".."john")
+        .await
+        .unwrap();
+    let _: Vec<RecordId> = client
+        .select(table.as_str())
+        .range("jane"..="john")
+        .await
+        .unwrap();
+    let _: Vec<RecordId> = client
+        .select(table.as_str())
+        .range("jane"..="john")
+        .await
+        .unwrap();
+    let _: Vec<RecordId> = client
+        .select(table.as_str())
+        .range((Bound::Excluded("jane"), Bound::Included("john")))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn update_table() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client.update(table).await.unwrap();
+}
+
+#[tokio::test]
+async fn update_record_id() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Option<RecordId> = client.update((table, "john")).await.unwrap();
+}
+
+#[tokio::test]
+async fn update_table_with_content() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client
+        .update(table)
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn update_record_range_with_content() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client
+        .update(table)
+        .range("jane".."john")
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn update_record_id_with_content() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Option<RecordId> = client
+        .update((table, "john"))
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn merge_table() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client
+        .update(table)
+        .merge(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn merge_record_range() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client
+        .update(table)
+        .range("jane".."john")
+        .merge(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn merge_record_id() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Option<RecordId> = client
+        .update((table, "john"))
+        .merge(Record { name: "John Doe" })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn patch_table() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client
+        .update(table)
+        .patch(PatchOp::replace("/baz", "boo"))
+        .patch(PatchOp::add("/hello", ["world"]))
+        .patch(PatchOp::remove("/foo"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn patch_record_range() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Vec<RecordId> = client
+        .update(table)
+        .range("jane".."john")
+        .patch(PatchOp::replace("/baz", "boo"))
+        .patch(PatchOp::add("/hello", ["world"]))
+        .patch(PatchOp::remove("/foo"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn patch_record_id() {
+    let table = Ulid::new().to_string();
+    let id = "record";
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: Option<RecordId> = client
+        .create((table.as_str(), id))
+        .content(json!({
+            "baz": "qux",
+            "foo": "bar"
+        }))
+        .await
+        .unwrap();
+    let _: Option<serde_json::Value> = client
+        .update((table.as_str(), id))
+        .patch(PatchOp::replace("/baz", "boo"))
+        .patch(PatchOp::add("/hello", ["world"]))
+        .patch(PatchOp::remove("/foo"))
+        .await
+        .unwrap();
+    let value: Option<serde_json::Value> = client.select((table.as_str(), id)).await.unwrap();
+    assert_eq!(
+        value,
+        Some(json!({
+            "id": format!("{table}:{id}"),
+            "baz": "boo",
+            "hello": ["world"]
+        }))
+    );
+}
+
+#[tokio::test]
+async fn delete_table() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    client.delete(table).await.unwrap();
+}
+
+#[tokio::test]
+async fn delete_record_id() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    client.delete((table, "john")).await.unwrap();
+}
+
+#[tokio::test]
+async fn delete_record_range() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    client.delete(table).range("jane".."john").await.unwrap();
+}
+
+#[tokio::test]
+async fn set_unset() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client
+        .set("user", Record { name: "John Doe" })
+        .await
+        .unwrap();
+    client.unset("user").await.unwrap();
+}
+
+#[tokio::test]
+async fn return_bool() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    client.query("RETURN true").await.unwrap();
+}
+
+#[tokio::test]
+async fn signin_unsupported() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    let error = client
+        .signin(Root {
+            username: ROOT_USER,
+            password: ROOT_PASS,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MethodNotSupported);
+}
+
+#[tokio::test]
+async fn version_unsupported() {
+    let client = Surreal::connect::<Mem>(()).await.unwrap();
+    let error = client.version().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::MethodNotSupported);
+}
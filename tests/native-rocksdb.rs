@@ -0,0 +1,136 @@
+#![cfg(feature = "rocksdb")]
+#![cfg(not(target_arch = "wasm32"))]
+
+#[allow(dead_code)]
+mod types;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use surrealdb_rs::protocol::File;
+use surrealdb_rs::Status;
+use surrealdb_rs::Surreal;
+use types::*;
+use ulid::Ulid;
+
+/// A directory for the files of a test, removed when the test is done with it
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        let path = env::temp_dir().join(format!("surrealdb-rs-{}", Ulid::new()));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn database(&self) -> PathBuf {
+        self.0.join("database")
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[tokio::test]
+async fn connect() {
+    let dir = TempDir::new();
+    let client = Surreal::connect::<File>(dir.database()).await.unwrap();
+    client.health().await.unwrap();
+}
+
+#[tokio::test]
+async fn query_take() {
+    let dir = TempDir::new();
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<File>(dir.database()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut response = client
+        .query("CREATE type::thing($table, john) SET name = 'John Doe'")
+        .query("SELECT * FROM type::table($table)")
+        .bind("table", table)
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    for stats in response.stats() {
+        assert_eq!(stats.status, Status::Ok);
+        assert!(stats.execution_time.is_some());
+    }
+    let john: Option<RecordId> = response.take(0).unwrap();
+    assert!(john.is_some());
+    let records: Vec<RecordId> = response.take(1).unwrap();
+    assert_eq!(records.len(), 1);
+}
+
+#[tokio::test]
+async fn create_select_delete() {
+    let dir = TempDir::new();
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<File>(dir.database()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: RecordId = client
+        .create((table.as_str(), "john"))
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+    let john: Option<RecordId> = client.select((table.as_str(), "john")).await.unwrap();
+    assert!(john.is_some());
+    client.delete((table.as_str(), "john")).await.unwrap();
+    let records: Vec<RecordId> = client.select(table.as_str()).await.unwrap();
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn export_import() {
+    let dir = TempDir::new();
+    let db = Ulid::new().to_string();
+    let client = Surreal::connect::<File>(dir.database()).await.unwrap();
+    client.use_ns(NS).use_db(&db).await.unwrap();
+    for i in 0..10 {
+        let _: RecordId = client
+            .create("user")
+            .content(Record {
+                name: &format!("User {i}"),
+            })
+            .await
+            .unwrap();
+    }
+    let file = dir.path().join(format!("{db}.sql"));
+    client.export(&file).await.unwrap();
+    client
+        .use_ns(NS)
+        .use_db(Ulid::new().to_string())
+        .await
+        .unwrap();
+    client.import(&file).await.unwrap();
+    let users: Vec<RecordId> = client.select("user").await.unwrap();
+    assert_eq!(users.len(), 10);
+}
+
+#[tokio::test]
+async fn data_persists_across_connections() {
+    let dir = TempDir::new();
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<File>(dir.database()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let _: RecordId = client
+        .create((table.as_str(), "john"))
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+    // the database has to be closed before it can be opened again
+    client.close().await.unwrap();
+    drop(client);
+    let client = Surreal::connect::<File>(dir.database()).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let john: Option<RecordId> = client.select((table.as_str(), "john")).await.unwrap();
+    assert!(john.is_some());
+}
@@ -4,10 +4,12 @@
 mod types;
 
 use crate::types::AuthParams;
+use futures::StreamExt;
 use serde_json::json;
 use std::ops::Bound;
 use surrealdb::sql::statements::BeginStatement;
 use surrealdb::sql::statements::CommitStatement;
+use surrealdb_rs::param::Action;
 use surrealdb_rs::param::Database;
 use surrealdb_rs::param::Jwt;
 use surrealdb_rs::param::NameSpace;
@@ -15,6 +17,8 @@ use surrealdb_rs::param::PatchOp;
 use surrealdb_rs::param::Root;
 use surrealdb_rs::param::Scope;
 use surrealdb_rs::protocol::Ws;
+use surrealdb_rs::ErrorKind;
+use surrealdb_rs::Status;
 use surrealdb_rs::Surreal;
 use types::*;
 use ulid::Ulid;
@@ -271,6 +275,37 @@ async fn query_binds() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn query_take() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut response = client
+        .query("CREATE type::thing($table, john) SET name = 'John Doe'")
+        .query("CREATE type::thing($table, jane) SET name = 'Jane Doe'")
+        .query("SELECT * FROM type::table($table)")
+        .query("SELECT name FROM type::table($table)")
+        .bind("table", table)
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    assert_eq!(response.len(), 4);
+    assert_eq!(response.stats().len(), 4);
+    for stats in response.stats() {
+        assert_eq!(stats.status, Status::Ok);
+        assert!(stats.execution_time.is_some());
+    }
+    let john: Option<RecordId> = response.take(0).unwrap();
+    assert!(john.is_some());
+    let name: Option<String> = response.take((1, "name")).unwrap();
+    assert_eq!(name.as_deref(), Some("Jane Doe"));
+    let records: Vec<RecordId> = response.take(2).unwrap();
+    assert_eq!(records.len(), 2);
+    let names: Vec<String> = response.take((3, "name")).unwrap();
+    assert_eq!(names.len(), 2);
+}
+
 #[tokio::test]
 async fn query_chaining() {
     let account = Ulid::new().to_string();
@@ -288,6 +323,84 @@ async fn query_chaining() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn transaction_commit() {
+    let account = Ulid::new().to_string();
+    let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut transaction = client.transaction().await.unwrap();
+    transaction
+        .create((account.as_str(), "one"))
+        .content(json!({ "balance": 135605.16 }));
+    transaction
+        .create((account.as_str(), "two"))
+        .content(json!({ "balance": 91031.31 }));
+    transaction
+        .update((account.as_str(), "one"))
+        .merge(json!({ "balance": 135905.16 }));
+    transaction
+        .query("UPDATE type::thing($table, two) SET balance -= 300.00")
+        .bind("table", &account);
+    transaction.select(account.as_str());
+    let results = transaction.commit().await.unwrap();
+    assert_eq!(results.len(), 5);
+    for result in results {
+        result.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn transaction_cancel() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut transaction = client.transaction().await.unwrap();
+    transaction.create(table.as_str());
+    transaction.cancel().await.unwrap();
+    let records: Vec<RecordId> = client.select(table.as_str()).await.unwrap();
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn transaction_with() {
+    let account = Ulid::new().to_string();
+    let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let results = client
+        .transaction_with(move |mut transaction| {
+            transaction
+                .query("CREATE type::thing($table, one) SET balance = 135605.16")
+                .query("UPDATE type::thing($table, one) SET balance += 300.00")
+                .bind("table", &account);
+            async move { Ok::<_, surrealdb_rs::Error>(transaction) }
+        })
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[tokio::test]
+async fn transaction_with_error() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let result =
+        client
+            .transaction_with({
+                let table = table.clone();
+                move |mut transaction| {
+                    transaction.create(table.as_str());
+                    async move {
+                        Err::<_, surrealdb_rs::Error>(ErrorKind::Query.with_message("aborted"))
+                    }
+                }
+            })
+            .await;
+    assert!(result.is_err());
+    let records: Vec<RecordId> = client.select(table.as_str()).await.unwrap();
+    assert!(records.is_empty());
+}
+
 #[tokio::test]
 async fn create_record_no_id() {
     let table = Ulid::new().to_string();
@@ -636,3 +749,26 @@ async fn return_bool() {
     let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
     client.query("RETURN true").await.unwrap();
 }
+
+#[tokio::test]
+async fn live_select() {
+    let table = Ulid::new().to_string();
+    let client = Surreal::connect::<Ws>(DB_ENDPOINT).await.unwrap();
+    client
+        .signin(Root {
+            username: ROOT_USER,
+            password: ROOT_PASS,
+        })
+        .await
+        .unwrap();
+    client.use_ns(NS).use_db(DB).await.unwrap();
+    let mut stream = client.live::<RecordId>(table.as_str()).await.unwrap();
+    let _: RecordId = client
+        .create((table.as_str(), "john"))
+        .content(Record { name: "John Doe" })
+        .await
+        .unwrap();
+    let notification = stream.next().await.unwrap().unwrap();
+    assert_eq!(notification.action, Action::Create);
+    assert_eq!(notification.data.id, format!("{table}:john"));
+}
@@ -0,0 +1,154 @@
+use chrono::DateTime;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use surrealdb::sql;
+use surrealdb::sql::Id;
+use surrealdb::sql::Number;
+use surrealdb::sql::Value;
+use surrealdb_rs::param::to_value;
+use surrealdb_rs::param::RecordId;
+
+#[derive(Debug, Serialize)]
+struct User {
+    id: RecordId,
+    name: String,
+    age: u8,
+    created: sql::Datetime,
+    timeout: sql::Duration,
+    nickname: Option<String>,
+}
+
+// types of our own that share their names with `SurrealDB` types
+#[derive(Debug, Serialize)]
+struct Thing {
+    tb: String,
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Datetime(String);
+
+#[derive(Debug, Serialize)]
+struct Duration {
+    secs: u64,
+    nanos: u32,
+}
+
+#[derive(Debug, Serialize)]
+enum Shape {
+    Point,
+    Circle(f64),
+}
+
+#[test]
+fn structs_keep_surrealdb_types() {
+    let id = RecordId {
+        tb: "user".to_owned(),
+        id: Id::String("john".to_owned()),
+    };
+    let created = sql::Datetime::default();
+    let user = User {
+        id: id.clone(),
+        name: "John Doe".to_owned(),
+        age: 30,
+        created: created.clone(),
+        timeout: std::time::Duration::from_secs(90).into(),
+        nickname: None,
+    };
+    let object = match to_value(user).unwrap() {
+        Value::Object(object) => object,
+        value => panic!("expected an object, found {value}"),
+    };
+    assert_eq!(object.get("id"), Some(&Value::Thing(id)));
+    assert_eq!(object.get("name"), Some(&Value::from("John Doe")));
+    assert_eq!(object.get("age"), Some(&Value::from(30_i64)));
+    assert_eq!(object.get("created"), Some(&Value::Datetime(created)));
+    assert_eq!(
+        object.get("timeout"),
+        Some(&Value::Duration(std::time::Duration::from_secs(90).into()))
+    );
+    assert_eq!(object.get("nickname"), Some(&Value::None));
+}
+
+#[test]
+fn large_integers_become_decimals() {
+    assert_eq!(to_value(42_u128).unwrap(), Value::from(42_i64));
+    let value = to_value(u128::from(u64::MAX) + 1).unwrap();
+    assert!(matches!(value, Value::Number(Number::Decimal(..))));
+    assert!(to_value(u128::MAX).is_err());
+}
+
+#[test]
+fn values_pass_through() {
+    let mut object = BTreeMap::new();
+    object.insert("name".to_owned(), Value::from("John Doe"));
+    object.insert("count".to_owned(), Value::from(2_i64));
+    object.insert(
+        "tags".to_owned(),
+        Value::from(vec![Value::from("a"), Value::Null]),
+    );
+    let value = Value::from(object);
+    assert_eq!(to_value(&value).unwrap(), value);
+}
+
+#[test]
+fn enums_are_externally_tagged() {
+    assert_eq!(to_value(Shape::Point).unwrap(), Value::from("Point"));
+    let mut circle = BTreeMap::new();
+    circle.insert("Circle".to_owned(), Value::from(1.5));
+    assert_eq!(to_value(Shape::Circle(1.5)).unwrap(), Value::from(circle));
+}
+
+#[test]
+fn other_types_keep_their_shape() {
+    let thing = to_value(Thing {
+        tb: "user".to_owned(),
+        id: "john".to_owned(),
+    })
+    .unwrap();
+    assert!(matches!(thing, Value::Object(..)));
+    let datetime = to_value(Datetime("2022-11-20T12:00:00Z".to_owned())).unwrap();
+    assert_eq!(datetime, Value::from("2022-11-20T12:00:00Z"));
+    let duration = to_value(Duration { secs: 90, nanos: 0 }).unwrap();
+    assert!(matches!(duration, Value::Object(..)));
+}
+
+// only the `sql` types are sent as datetimes, UUIDs and decimals
+#[test]
+fn wrapped_types_serialize_as_strings() {
+    let datetime = DateTime::parse_from_rfc3339("2022-11-20T12:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    assert!(matches!(to_value(datetime).unwrap(), Value::Strand(..)));
+    assert!(matches!(
+        to_value(sql::Datetime::from(datetime)).unwrap(),
+        Value::Datetime(..)
+    ));
+    let uuid = uuid::Uuid::nil();
+    assert_eq!(to_value(uuid).unwrap(), Value::from(uuid.to_string()));
+    assert_eq!(
+        to_value(sql::Uuid::from(uuid)).unwrap(),
+        Value::Uuid(uuid.into())
+    );
+    let decimal = Decimal::from_str("1.5").unwrap();
+    assert_eq!(to_value(decimal).unwrap(), Value::from("1.5"));
+    assert_eq!(
+        to_value(Number::Decimal(decimal)).unwrap(),
+        Value::Number(Number::Decimal(decimal))
+    );
+}
+
+#[test]
+fn values_json_cannot_express_pass_through() {
+    let point = Value::Geometry(sql::Geometry::Point((-0.118092, 51.509865).into()));
+    assert_eq!(to_value(&point).unwrap(), point);
+    let table = Value::Table(sql::Table("user".to_owned()));
+    assert_eq!(to_value(&table).unwrap(), table);
+    let mut object = BTreeMap::new();
+    object.insert("location".to_owned(), point);
+    let value = Value::from(object);
+    assert_eq!(to_value(&value).unwrap(), value);
+}
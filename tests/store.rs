@@ -0,0 +1,28 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::fs;
+use surrealdb::sql::Value;
+use surrealdb_rs::param::FileStore;
+use surrealdb_rs::param::Jwt;
+use surrealdb_rs::param::SavedSession;
+use surrealdb_rs::param::SessionStore;
+
+#[test]
+fn file_store() {
+    let path = std::env::temp_dir().join(format!("surrealdb-session-{}", std::process::id()));
+    let store = FileStore::new(&path);
+    assert!(store.load().unwrap().is_none());
+    let mut saved = SavedSession::default();
+    saved.token = Some(Jwt::from("token"));
+    saved.namespace = Some("test-ns".to_owned());
+    saved.vars.insert("name".to_owned(), "John Doe".into());
+    saved.vars.insert("age".to_owned(), 42.into());
+    store.save(&saved).unwrap();
+    let loaded = store.load().unwrap().unwrap();
+    assert_eq!(loaded.token.unwrap().as_insecure_token(), "token");
+    assert_eq!(loaded.namespace.as_deref(), Some("test-ns"));
+    assert_eq!(loaded.database, None);
+    assert_eq!(loaded.vars["name"], Value::from("John Doe"));
+    assert_eq!(loaded.vars["age"], Value::from(42));
+    fs::remove_file(&path).unwrap();
+}